@@ -0,0 +1,33 @@
+//! Times `DenseGame` against `Game` stepping the same soup on a 1920x1080 grid at cube size 1
+//!
+//! ```text
+//! cargo run --release -p gol-engine --example dense_vs_game
+//! ```
+use gol_engine::{DenseGame, Engine, Game, Soup};
+use std::time::{Duration, Instant};
+
+const COLUMNS: u32 = 1920;
+const ROWS: u32 = 1080;
+
+/// Average time `engine` takes to step one of `generations` generations
+fn time_steps(engine: &mut dyn Engine, generations: u32) -> Duration {
+    Soup::new(1).fill(engine, 0, 0, COLUMNS, ROWS);
+    let start = Instant::now();
+    for _ in 0..generations {
+        engine.step(1);
+    }
+    start.elapsed() / generations
+}
+
+fn main() {
+    // Game takes seconds per generation at this size
+    let game = time_steps(&mut Game::new(COLUMNS, ROWS, 1), 3);
+    let dense = time_steps(&mut DenseGame::new(COLUMNS, ROWS), 100);
+    println!("{}x{} soup", COLUMNS, ROWS);
+    println!("Game:      {:>10.3?} per generation", game);
+    println!("DenseGame: {:>10.3?} per generation", dense);
+    println!(
+        "DenseGame is {:.0}x faster",
+        game.as_secs_f64() / dense.as_secs_f64()
+    );
+}
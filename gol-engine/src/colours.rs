@@ -0,0 +1,107 @@
+//! Multi-colour Life variants, where every alive cell belongs to a team
+use std::{fmt, str::FromStr};
+
+/// How many teams the alive cells of a [`crate::Game`] are split between, and how newborns pick one.
+///
+/// Teams are numbered from 1. A cell keeps its team while it survives, and a newborn joins the team most
+/// of its parents (the alive neighbors that gave birth to it) are in
+///
+/// ```
+/// use gol_engine::Colours;
+///
+/// assert_eq!(Colours::Immigration.newborn(&[2, 1, 2]), 2);
+/// // three parents from three teams: QuadLife picks the fourth one
+/// assert_eq!(Colours::QuadLife.newborn(&[1, 4, 2]), 3);
+/// assert_eq!(Colours::QuadLife.newborn(&[3, 3, 1]), 3);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Colours {
+    /// Plain Life, a single team
+    #[default]
+    Single,
+    /// 2 teams
+    Immigration,
+    /// 4 teams. A cell born from 3 parents of 3 different teams joins the missing one
+    QuadLife,
+}
+
+impl Colours {
+    /// All of the variants, in the order they're listed on the CLI
+    pub const ALL: [Colours; 3] = [Colours::Single, Colours::Immigration, Colours::QuadLife];
+
+    /// How many teams there are
+    pub fn count(&self) -> u8 {
+        match self {
+            Colours::Single => 1,
+            Colours::Immigration => 2,
+            Colours::QuadLife => 4,
+        }
+    }
+
+    /// Team a cell born from `parents` (the teams of its alive neighbors) joins: the one most of them
+    /// are in. When that's a tie, QuadLife picks the only team none of them are in, if there's one.
+    /// Other ties go to the lowest team
+    pub fn newborn(&self, parents: &[u8]) -> u8 {
+        let mut counts = vec![0; self.count() as usize];
+        for &parent in parents {
+            if let Some(count) = (parent as usize)
+                .checked_sub(1)
+                .and_then(|i| counts.get_mut(i))
+            {
+                *count += 1;
+            }
+        }
+        let most = counts.iter().copied().max().unwrap_or(0);
+        let tied = (1..=self.count())
+            .filter(|&team| counts[team as usize - 1] == most)
+            .collect::<Vec<_>>();
+        let missing = (1..=self.count())
+            .filter(|&team| counts[team as usize - 1] == 0)
+            .collect::<Vec<_>>();
+        match (self, &tied[..], &missing[..]) {
+            (_, [team], _) => *team,
+            (Colours::QuadLife, _, [team]) => *team,
+            _ => tied[0],
+        }
+    }
+}
+
+/// Error returned when a colour variant name isn't known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColoursError(String);
+
+impl fmt::Display for ParseColoursError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown colours `{}` (expected one of: {})",
+            self.0,
+            Colours::ALL.map(|c| c.to_string()).join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ParseColoursError {}
+
+impl FromStr for Colours {
+    type Err = ParseColoursError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "single" | "none" => Ok(Colours::Single),
+            "immigration" => Ok(Colours::Immigration),
+            "quadlife" => Ok(Colours::QuadLife),
+            _ => Err(ParseColoursError(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Colours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Colours::Single => "single",
+            Colours::Immigration => "immigration",
+            Colours::QuadLife => "quadlife",
+        })
+    }
+}
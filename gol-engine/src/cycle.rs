@@ -0,0 +1,126 @@
+//! Spotting when a universe starts repeating itself
+use crate::engine::{cell_hash, Engine};
+use crate::game::LifeState;
+use std::collections::{HashMap, VecDeque};
+
+/// A universe that keeps coming back to the same state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// First generation of the cycle
+    pub start: u128,
+    /// How many generations it takes to come back (1 for still lifes and dead universes)
+    pub period: u128,
+}
+
+/// Remembers the [`Engine::cells_hash`] of the last generations it was shown and finds the first one
+/// that comes back.
+///
+/// Only exact repeats count: a glider flying away never repeats. Periods are only as fine as the steps
+/// between calls to [`CycleDetector::observe`] (jumping 2^k generations at a time finds multiples of 2^k)
+///
+/// ```
+/// use gol_engine::{Cycle, CycleDetector, Engine, LifeState, SparseGame};
+///
+/// let mut game = SparseGame::new();
+/// for x in 0..3 {
+///     game.set_cell(x, 0, LifeState::Alive);
+/// }
+/// let mut detector = CycleDetector::new(100);
+/// while detector.observe(&game).is_none() {
+///     Engine::step(&mut game, 1);
+/// }
+/// assert_eq!(detector.cycle(), Some(Cycle { start: 0, period: 2 }));
+/// ```
+pub struct CycleDetector {
+    /// Generation each remembered hash was seen at
+    seen: HashMap<u64, u128>,
+    /// Remembered hashes, oldest first
+    order: VecDeque<u64>,
+    /// How many generations are remembered (the longest period that can be found)
+    window: usize,
+    cycle: Option<Cycle>,
+    /// Hash of the last generation observed, that [`CycleDetector::observe_changes`] builds on
+    hash: Option<u64>,
+}
+
+impl CycleDetector {
+    /// Detector finding periods up to `window` observations long
+    pub fn new(window: usize) -> Self {
+        Self {
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+            window: window.max(1),
+            cycle: None,
+            hash: None,
+        }
+    }
+
+    /// The cycle found so far, if any
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Forget everything (after the cells were edited or rewound)
+    pub fn clear(&mut self) {
+        self.seen.clear();
+        self.order.clear();
+        self.cycle = None;
+        self.hash = None;
+    }
+
+    /// Whether nothing was observed since the detector was built or cleared
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Look at the current generation of `engine`. Returns the cycle once one has been found
+    pub fn observe<E: Engine + ?Sized>(&mut self, engine: &E) -> Option<Cycle> {
+        if self.cycle.is_some() {
+            return self.cycle;
+        }
+        self.observe_hash(engine.cells_hash(), engine.generation())
+    }
+
+    /// Same as [`CycleDetector::observe`] right after a step that changed `changes` (as listed by
+    /// [`Engine::step_with_changes`]) from the last generation observed. Only the changed cells are
+    /// hashed, so it costs nothing on still grids
+    pub fn observe_changes<E: Engine + ?Sized>(
+        &mut self,
+        engine: &E,
+        changes: &[((i64, i64), LifeState)],
+    ) -> Option<Cycle> {
+        let Some(hash) = self.hash else {
+            return self.observe(engine);
+        };
+        let hash = changes.iter().fold(hash, |hash, &(pos, old)| {
+            hash.wrapping_sub(cell_hash(pos, old))
+                .wrapping_add(cell_hash(pos, engine.get_cell(pos.0, pos.1)))
+        });
+        self.observe_hash(hash, engine.generation())
+    }
+
+    fn observe_hash(&mut self, hash: u64, generation: u128) -> Option<Cycle> {
+        self.hash = Some(hash);
+        if self.cycle.is_some() {
+            return self.cycle;
+        }
+        if let Some(&start) = self.seen.get(&hash) {
+            // seeing the same generation twice isn't a cycle
+            if start < generation {
+                self.cycle = Some(Cycle {
+                    start,
+                    period: generation - start,
+                });
+            }
+            return self.cycle;
+        }
+        self.seen.insert(hash, generation);
+        self.order.push_back(hash);
+        if self.order.len() > self.window {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,446 @@
+//! Dense bit-packed grid stepped 64 cells at a time
+use crate::engine::{grid_coordinates, Engine};
+use crate::game::LifeState;
+use crate::rule::Rule;
+use crate::stats::StepStats;
+use crate::topology::Topology;
+
+/// Bit-packed counterpart of [`crate::Game`]: one bit per cell, 64 cells per [`u64`].
+///
+/// The grid is stored with a one cell wide border of ghost cells around it. Before each step the border is
+/// filled from the [`Topology`], so the step itself is just bitwise adders and never looks up a single cell.
+///
+/// Only alive and dead exist here, so Generations rules lose their dying states (dying cells are dead).
+/// Neighbors are only counted, so non-totalistic rules only get the counts every arrangement matches, and
+/// cells are always squares (the [`crate::Tiling`] of the rule is ignored)
+pub struct DenseGame {
+    columns: u32,
+    rows: u32,
+    /// [`u64`]s per padded row
+    stride: usize,
+    cells: Vec<u64>,
+    /// Spare buffer the next generation is written into
+    next: Vec<u64>,
+    /// Bits of a padded row that are real cells (no ghosts, no padding past the end)
+    inner_mask: Vec<u64>,
+    generation: u64,
+    rule: Rule,
+    topology: Topology,
+    threads: usize,
+    /// What the last generation changed
+    last_step: StepStats,
+}
+
+/// (sum, carry) of adding 3 bits in every lane
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    (a ^ b ^ c, (a & b) | (c & (a ^ b)))
+}
+
+impl DenseGame {
+    /// Build a `columns` x `rows` grid of dead cells
+    pub fn new(columns: u32, rows: u32) -> Self {
+        let stride = (columns as usize + 2).div_ceil(64);
+        let size = stride * (rows as usize + 2);
+        let inner_mask = (0..stride)
+            .map(|i| {
+                (0..64)
+                    .filter(|b| (1..=columns as usize).contains(&(i * 64 + b)))
+                    .fold(0, |m, b| m | (1 << b))
+            })
+            .collect();
+        Self {
+            columns,
+            rows,
+            stride,
+            cells: vec![0; size],
+            next: vec![0; size],
+            inner_mask,
+            generation: 0,
+            rule: Rule::default(),
+            topology: Topology::default(),
+            threads: 1,
+            last_step: StepStats::default(),
+        }
+    }
+
+    /// How many cells there are on each row
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// How many rows there are in the grid
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// [`Rule`] used to decide births and survivals
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Replace the [`Rule`] used for the next generations
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// [`Topology`] deciding what's on the other side of the grid edges
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Change how the grid edges are glued together
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// How many threads [`DenseGame::step`] splits the rows between
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Step with `threads` threads, each one taking a stripe of rows. The result doesn't depend on it
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// How many generations have been simulated so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Births, deaths and changed cells of the last generation
+    pub fn last_step(&self) -> StepStats {
+        self.last_step
+    }
+
+    /// Word index and bit of padded cell (`px`, `py`)
+    fn locate(&self, px: usize, py: usize) -> (usize, u64) {
+        (py * self.stride + px / 64, 1 << (px % 64))
+    }
+
+    fn inside(&self, x: i32, y: i32) -> bool {
+        (0..self.columns as i32).contains(&x) && (0..self.rows as i32).contains(&y)
+    }
+
+    /// [`LifeState`] of the cell at column `x` and row `y` (`None` if it's outside of the grid)
+    pub fn get_cell(&self, x: i32, y: i32) -> Option<LifeState> {
+        if !self.inside(x, y) {
+            return None;
+        }
+        let (i, bit) = self.locate(x as usize + 1, y as usize + 1);
+        Some(if self.cells[i] & bit != 0 {
+            LifeState::Alive
+        } else {
+            LifeState::Dead
+        })
+    }
+
+    /// Set the [`LifeState`] of the cell at column `x` and row `y`. Cells outside of the grid are ignored
+    pub fn set_cell(&mut self, x: i32, y: i32, state: LifeState) {
+        if !self.inside(x, y) {
+            return;
+        }
+        let (i, bit) = self.locate(x as usize + 1, y as usize + 1);
+        if state == LifeState::Alive {
+            self.cells[i] |= bit;
+        } else {
+            self.cells[i] &= !bit;
+        }
+    }
+
+    /// Flip the cell at column `x` and row `y` between alive and dead
+    pub fn toggle_cell(&mut self, x: i32, y: i32) {
+        if self.inside(x, y) {
+            let (i, bit) = self.locate(x as usize + 1, y as usize + 1);
+            self.cells[i] ^= bit;
+        }
+    }
+
+    /// Iterate over the (column, row) of every alive cell, row by row
+    pub fn alive_cells(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        (0..self.rows as usize).flat_map(move |y| {
+            let row = &self.cells[(y + 1) * self.stride..(y + 2) * self.stride];
+            row.iter()
+                .zip(&self.inner_mask)
+                .enumerate()
+                .flat_map(move |(i, (&word, &mask))| {
+                    let mut word = word & mask;
+                    std::iter::from_fn(move || {
+                        if word == 0 {
+                            return None;
+                        }
+                        let b = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        Some(((i * 64 + b - 1) as i32, y as i32))
+                    })
+                })
+        })
+    }
+
+    /// How many cells are currently alive
+    pub fn population(&self) -> usize {
+        self.cells
+            .chunks(self.stride)
+            .skip(1)
+            .take(self.rows as usize)
+            .map(|row| {
+                row.iter()
+                    .zip(&self.inner_mask)
+                    .map(|(w, m)| (w & m).count_ones() as usize)
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Kill every cell in the grid
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+    }
+
+    /// Copy the cells on the other side of each edge into the ghost border
+    fn fill_border(&mut self) {
+        let (columns, rows) = (self.columns as i32, self.rows as i32);
+        let ghosts = (-1..=columns)
+            .flat_map(|x| [(x, -1), (x, rows)])
+            .chain((0..rows).flat_map(|y| [(-1, y), (columns, y)]));
+        for (x, y) in ghosts {
+            let alive = self
+                .topology
+                .wrap(x, y, columns, rows)
+                .and_then(|(wx, wy)| self.get_cell(wx, wy))
+                == Some(LifeState::Alive);
+            let (i, bit) = self.locate((x + 1) as usize, (y + 1) as usize);
+            if alive {
+                self.cells[i] |= bit;
+            } else {
+                self.cells[i] &= !bit;
+            }
+        }
+    }
+
+    /// Advance the grid by one generation
+    pub fn step(&mut self) {
+        self.fill_border();
+        let stride = self.stride;
+        let rows = self.rows as usize;
+        let stepper = RowStepper {
+            cells: &self.cells,
+            inner_mask: &self.inner_mask,
+            stride,
+            born: std::array::from_fn(|n| self.rule.born(n)),
+            survives: std::array::from_fn(|n| self.rule.survives(n)),
+        };
+        let interior = &mut self.next[stride..(rows + 1) * stride];
+        let threads = self.threads.clamp(1, rows.max(1));
+        if threads == 1 {
+            stepper.step_rows(1, interior);
+        } else {
+            // every thread gets its own stripe of rows. Stripes only read the shared current generation
+            let rows_per_thread = rows.div_ceil(threads);
+            std::thread::scope(|scope| {
+                for (t, stripe) in interior.chunks_mut(rows_per_thread * stride).enumerate() {
+                    let stepper = &stepper;
+                    scope.spawn(move || stepper.step_rows(1 + t * rows_per_thread, stripe));
+                }
+            });
+        }
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.last_step = self.count_changes();
+        self.generation += 1;
+    }
+
+    /// Cells that differ between `before` (an earlier copy of `cells`) and now, with their state in `before`.
+    /// Only words that changed are looked into
+    fn changes_since(&self, before: &[u64]) -> Vec<((i64, i64), LifeState)> {
+        let interior = self.stride..(self.rows as usize + 1) * self.stride;
+        let words = || {
+            self.cells[interior.clone()]
+                .iter()
+                .zip(&before[interior.clone()])
+                .enumerate()
+        };
+        // counting first saves growing the list over and over on busy grids
+        let mut changes = Vec::with_capacity(
+            words()
+                .map(|(i, (now, before))| {
+                    ((now ^ before) & self.inner_mask[i % self.stride]).count_ones() as usize
+                })
+                .sum(),
+        );
+        for (i, (now, before)) in words() {
+            let mut changed = (now ^ before) & self.inner_mask[i % self.stride];
+            while changed != 0 {
+                let b = changed.trailing_zeros() as usize;
+                changed &= changed - 1;
+                let x = (i % self.stride) * 64 + b - 1;
+                let state = if before & (1 << b) != 0 {
+                    LifeState::Alive
+                } else {
+                    LifeState::Dead
+                };
+                changes.push(((x as i64, (i / self.stride) as i64), state));
+            }
+        }
+        changes
+    }
+
+    /// Compare the generation that was just stepped to (`cells`) to the one before (`next`)
+    fn count_changes(&self) -> StepStats {
+        let mut stats = StepStats::default();
+        let interior = self.stride..(self.rows as usize + 1) * self.stride;
+        for (i, (now, before)) in self.cells[interior.clone()]
+            .iter()
+            .zip(&self.next[interior])
+            .enumerate()
+        {
+            let mask = self.inner_mask[i % self.stride];
+            stats.births += (now & !before & mask).count_ones() as u64;
+            stats.deaths += (!now & before & mask).count_ones() as u64;
+        }
+        stats.changed = stats.births + stats.deaths;
+        stats
+    }
+}
+
+/// Everything needed to step a stripe of rows, shared between threads
+struct RowStepper<'a> {
+    cells: &'a [u64],
+    inner_mask: &'a [u64],
+    stride: usize,
+    born: [bool; 9],
+    survives: [bool; 9],
+}
+
+impl RowStepper<'_> {
+    /// Write the next generation of the padded rows starting at `first_row` into `next`
+    fn step_rows(&self, first_row: usize, next: &mut [u64]) {
+        let (cells, stride) = (self.cells, self.stride);
+        // west neighbors of word `i` in row `r` sit one bit lower, east neighbors one bit higher
+        let west = |r: usize, i: usize| {
+            let w = cells[r * stride + i] << 1;
+            if i > 0 {
+                w | (cells[r * stride + i - 1] >> 63)
+            } else {
+                w
+            }
+        };
+        let east = |r: usize, i: usize| {
+            let w = cells[r * stride + i] >> 1;
+            if i + 1 < stride {
+                w | (cells[r * stride + i + 1] << 63)
+            } else {
+                w
+            }
+        };
+        for (row, next) in next.chunks_mut(stride).enumerate() {
+            let r = first_row + row;
+            for (i, next) in next.iter_mut().enumerate() {
+                let alive = cells[r * stride + i];
+                let (s_a, c_a) =
+                    full_add(west(r - 1, i), cells[(r - 1) * stride + i], east(r - 1, i));
+                let (s_b, c_b) = full_add(west(r, i), east(r, i), west(r + 1, i));
+                let (s_c, c_c) = (
+                    cells[(r + 1) * stride + i] ^ east(r + 1, i),
+                    cells[(r + 1) * stride + i] & east(r + 1, i),
+                );
+                // neighbor count as 4 bit planes (1s, 2s, 4s and 8s)
+                let (ones, c_d) = full_add(s_a, s_b, s_c);
+                let (t, c_e) = full_add(c_a, c_b, c_c);
+                let (twos, c_f) = (t ^ c_d, t & c_d);
+                let (fours, eights) = (c_e ^ c_f, c_e & c_f);
+                let mut word = 0;
+                for n in 0..9 {
+                    if !self.born[n] && !self.survives[n] {
+                        continue;
+                    }
+                    let pick = |plane: u64, bit: usize| if n & bit != 0 { plane } else { !plane };
+                    let count_is_n =
+                        pick(ones, 1) & pick(twos, 2) & pick(fours, 4) & pick(eights, 8);
+                    if self.born[n] {
+                        word |= count_is_n & !alive;
+                    }
+                    if self.survives[n] {
+                        word |= count_is_n & alive;
+                    }
+                }
+                *next = word & self.inner_mask[i];
+            }
+        }
+    }
+}
+
+impl Engine for DenseGame {
+    fn name(&self) -> &'static str {
+        "dense"
+    }
+
+    fn rule_name(&self) -> String {
+        self.rule.to_string()
+    }
+
+    /// Dying states are dropped, so there's only alive and dead
+    fn states(&self) -> u8 {
+        2
+    }
+
+    fn topology(&self) -> Option<Topology> {
+        Some(self.topology)
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        grid_coordinates(x, y)
+            .and_then(|(x, y)| DenseGame::get_cell(self, x, y))
+            .unwrap_or(LifeState::Dead)
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        if let Some((x, y)) = grid_coordinates(x, y) {
+            DenseGame::set_cell(self, x, y, state);
+        }
+    }
+
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            DenseGame::step(self);
+        }
+    }
+
+    fn step_with_changes(&mut self, generations: u64) -> Option<Vec<((i64, i64), LifeState)>> {
+        if generations == 1 {
+            // the generation before is still in the spare buffer
+            DenseGame::step(self);
+            return Some(self.changes_since(&self.next));
+        }
+        let before = self.cells.clone();
+        Engine::step(self, generations);
+        Some(self.changes_since(&before))
+    }
+
+    fn can_rewind(&self) -> bool {
+        true
+    }
+
+    fn last_step(&self) -> Option<StepStats> {
+        Some(self.last_step)
+    }
+
+    fn generation(&self) -> u128 {
+        self.generation as u128
+    }
+
+    fn set_generation(&mut self, generation: u128) {
+        self.generation = u64::try_from(generation).unwrap_or(u64::MAX);
+    }
+
+    fn population(&self) -> u64 {
+        DenseGame::population(self) as u64
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (i64, i64)> + '_> {
+        Box::new(self.alive_cells().map(|(x, y)| (x as i64, y as i64)))
+    }
+
+    fn clear(&mut self) {
+        DenseGame::clear(self);
+    }
+}
@@ -0,0 +1,293 @@
+//! Wolfram's elementary automata: one row of cells, drawn as a spacetime diagram growing downwards
+use crate::engine::{grid_coordinates, Engine};
+use crate::game::LifeState;
+use crate::rule::ParseRuleError;
+use crate::topology::Topology;
+use std::{fmt, str::FromStr};
+
+/// Elementary rule in Wolfram's numbering (`W30`, `W90`, `W110`...).
+///
+/// Bit `4 left + 2 middle + right` of the number is the next state of a cell whose left neighbor, itself and
+/// right neighbor are `left`, `middle` and `right`
+///
+/// ```
+/// use gol_engine::ElementaryRule;
+///
+/// let rule: ElementaryRule = "W110".parse().unwrap();
+/// assert_eq!(rule.number(), 110);
+/// assert!(rule.next_state(false, true, true));
+/// assert!(!rule.next_state(true, true, true));
+/// assert_eq!(rule.to_string(), "W110");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ElementaryRule(u8);
+
+impl ElementaryRule {
+    /// Rule with Wolfram number `number`
+    pub fn new(number: u8) -> Self {
+        Self(number)
+    }
+
+    /// Wolfram number of the rule
+    pub fn number(&self) -> u8 {
+        self.0
+    }
+
+    /// Is a cell alive next generation when its left neighbor, itself and its right neighbor are alive or not?
+    pub fn next_state(&self, left: bool, middle: bool, right: bool) -> bool {
+        self.0 >> ((left as u8) << 2 | (middle as u8) << 1 | right as u8) & 1 == 1
+    }
+}
+
+impl FromStr for ElementaryRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        s.strip_prefix(['W', 'w'])
+            .and_then(|n| n.parse::<u8>().ok())
+            .map(Self)
+            .ok_or_else(|| {
+                ParseRuleError(format!(
+                    "elementary rules are a W and a number from 0 to 255, not `{}`",
+                    s
+                ))
+            })
+    }
+}
+
+impl fmt::Display for ElementaryRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "W{}", self.0)
+    }
+}
+
+/// Spacetime diagram of an [`ElementaryRule`]: a `columns` wide row of cells with one generation per row.
+///
+/// The diagram grows downwards from row 0 until it's `rows` tall, then every step scrolls it up by one row.
+/// Only the row of the current generation decides what comes next. Rows past it are the future, so
+/// writes to them are ignored. The ends of the row are glued together like the left and right edges of
+/// the [`Topology`]
+pub struct ElementaryGame {
+    columns: u32,
+    rows: u32,
+    /// Every row of the diagram, top to bottom
+    cells: Vec<bool>,
+    generation: u64,
+    /// Generation the diagram was last cleared at, it sits on row 0
+    origin: u64,
+    rule: ElementaryRule,
+    topology: Topology,
+}
+
+impl ElementaryGame {
+    /// Build an empty `columns` x `rows` diagram running `rule`
+    pub fn new(columns: u32, rows: u32, rule: ElementaryRule) -> Self {
+        Self {
+            columns,
+            rows,
+            cells: vec![false; columns as usize * rows as usize],
+            generation: 0,
+            origin: 0,
+            rule,
+            topology: Topology::default(),
+        }
+    }
+
+    /// Width of the diagram, the length of the row of cells
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Height of the diagram, how many generations it shows at once
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// [`ElementaryRule`] deciding the next row
+    pub fn rule(&self) -> ElementaryRule {
+        self.rule
+    }
+
+    /// Replace the [`ElementaryRule`] used for the next generations
+    pub fn set_rule(&mut self, rule: ElementaryRule) {
+        self.rule = rule;
+    }
+
+    /// [`Topology`] deciding what's past both ends of the row
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Change how the ends of the row are glued together. A single row can't be folded into a
+    /// [`Topology::Sphere`], its ends are glued like on a [`Topology::Torus`] instead
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = match topology {
+            Topology::Sphere => Topology::Torus,
+            topology => topology,
+        };
+    }
+
+    /// How many generations have been simulated so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Row of the diagram holding the current generation
+    pub fn current_row(&self) -> u32 {
+        self.generation
+            .saturating_sub(self.origin)
+            .min(self.rows.saturating_sub(1) as u64) as u32
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if (0..self.columns as i32).contains(&x) && (0..self.rows as i32).contains(&y) {
+            Some(y as usize * self.columns as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the cell at column `x` and row `y` of the diagram is alive (`None` if it's outside of it)
+    pub fn get_cell(&self, x: i32, y: i32) -> Option<bool> {
+        self.index(x, y).map(|i| self.cells[i])
+    }
+
+    /// Set the cell at column `x` and row `y` of the diagram. Cells outside of it or past the current
+    /// generation are ignored
+    pub fn set_cell(&mut self, x: i32, y: i32, alive: bool) {
+        if y > self.current_row() as i32 {
+            return;
+        }
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = alive;
+        }
+    }
+
+    /// How many cells of the whole diagram are alive
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&c| c).count()
+    }
+
+    /// Empty the diagram and start again from row 0
+    pub fn clear(&mut self) {
+        self.cells.fill(false);
+        self.origin = self.generation;
+    }
+
+    /// Work out the next generation, one row further down
+    pub fn step(&mut self) {
+        if self.rows == 0 {
+            return;
+        }
+        let columns = self.columns as usize;
+        let row = self.current_row() as usize;
+        let alive = |x: i32| {
+            self.topology
+                .wrap(x, 0, columns as i32, 1)
+                .is_some_and(|(x, _)| self.cells[row * columns + x as usize])
+        };
+        let next = (0..columns as i32)
+            .map(|x| self.rule.next_state(alive(x - 1), alive(x), alive(x + 1)))
+            .collect::<Vec<_>>();
+        let row = if row + 1 == self.rows as usize {
+            // the diagram is full, make room at the bottom
+            self.cells.copy_within(columns.., 0);
+            row
+        } else {
+            row + 1
+        };
+        self.cells[row * columns..(row + 1) * columns].copy_from_slice(&next);
+        self.generation += 1;
+    }
+}
+
+impl Engine for ElementaryGame {
+    fn name(&self) -> &'static str {
+        "elementary"
+    }
+
+    fn rule_name(&self) -> String {
+        self.rule.to_string()
+    }
+
+    fn states(&self) -> u8 {
+        2
+    }
+
+    fn topology(&self) -> Option<Topology> {
+        Some(self.topology)
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        match grid_coordinates(x, y).and_then(|(x, y)| ElementaryGame::get_cell(self, x, y)) {
+            Some(true) => LifeState::Alive,
+            _ => LifeState::Dead,
+        }
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        if let Some((x, y)) = grid_coordinates(x, y) {
+            ElementaryGame::set_cell(self, x, y, state == LifeState::Alive);
+        }
+    }
+
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            ElementaryGame::step(self);
+        }
+    }
+
+    /// Only the rows written below the current one change, and they were empty. Scrolling a full diagram
+    /// changes about every cell, so those steps aren't listed (they can't be rewound)
+    fn step_with_changes(&mut self, generations: u64) -> Option<Vec<((i64, i64), LifeState)>> {
+        let row = self.current_row() as u64;
+        Engine::step(self, generations);
+        if row.saturating_add(generations) >= self.rows as u64 {
+            return None;
+        }
+        let columns = self.columns as usize;
+        let start = (row as usize + 1) * columns;
+        let end = start + generations as usize * columns;
+        Some(
+            (start..end)
+                .filter(|&i| self.cells[i])
+                .map(|i| {
+                    let pos = ((i % columns) as i64, (i / columns) as i64);
+                    (pos, LifeState::Dead)
+                })
+                .collect(),
+        )
+    }
+
+    fn can_rewind(&self) -> bool {
+        true
+    }
+
+    fn generation(&self) -> u128 {
+        self.generation as u128
+    }
+
+    fn set_generation(&mut self, generation: u128) {
+        self.generation = u64::try_from(generation).unwrap_or(u64::MAX);
+    }
+
+    fn population(&self) -> u64 {
+        ElementaryGame::population(self) as u64
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (i64, i64)> + '_> {
+        let columns = self.columns as usize;
+        Box::new(
+            self.cells
+                .iter()
+                .enumerate()
+                .filter(|(_, &c)| c)
+                .map(move |(i, _)| ((i % columns) as i64, (i / columns) as i64)),
+        )
+    }
+
+    fn clear(&mut self) {
+        ElementaryGame::clear(self);
+    }
+}
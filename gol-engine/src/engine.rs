@@ -0,0 +1,148 @@
+//! Common interface of every simulation backend
+use crate::game::LifeState;
+use crate::stats::StepStats;
+use crate::topology::Topology;
+
+/// A simulation backend ([`crate::Game`], [`crate::DenseGame`], [`crate::SparseGame`], [`crate::HashLifeGame`]...).
+///
+/// Cells are addressed with [`i64`] coordinates everywhere. Bounded backends treat anything outside of
+/// their grid as dead and ignore writes to it
+pub trait Engine {
+    /// Short name of the backend (as used by `--engine`)
+    fn name(&self) -> &'static str;
+
+    /// Rulestring of the rule the backend runs (`B3/S23`, `R5,C0,M1,S34..58,B34..45,NM`...)
+    fn rule_name(&self) -> String;
+
+    /// Total amount of states a cell can be in (2 unless the rule has dying states or is a rule table)
+    fn states(&self) -> u8;
+
+    /// [`Topology`] of a bounded backend (`None` when the universe is unbounded)
+    fn topology(&self) -> Option<Topology> {
+        None
+    }
+
+    /// [`LifeState`] of the cell at (`x`, `y`)
+    fn get_cell(&self, x: i64, y: i64) -> LifeState;
+
+    /// Set the [`LifeState`] of the cell at (`x`, `y`)
+    fn set_cell(&mut self, x: i64, y: i64, state: LifeState);
+
+    /// Value from 0 to 1 of the cell at (`x`, `y`) for continuous backends like [`crate::LeniaGame`]
+    /// (`None` when cells only have discrete states)
+    fn value(&self, _x: i64, _y: i64) -> Option<f32> {
+        None
+    }
+
+    /// Flip the cell at (`x`, `y`) between alive and dead (dying cells come back alive)
+    fn toggle_cell(&mut self, x: i64, y: i64) {
+        self.paint_cell(x, y, LifeState::Alive);
+    }
+
+    /// Flip the cell at (`x`, `y`) between `state` and dead (cells in any other state become `state`)
+    fn paint_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        let state = if self.get_cell(x, y) == state {
+            LifeState::Dead
+        } else {
+            state
+        };
+        self.set_cell(x, y, state);
+    }
+
+    /// Advance `generations` generations
+    fn step(&mut self, generations: u64);
+
+    /// Advance `generations` generations like [`Engine::step`], and list the cells that changed with the
+    /// [`LifeState`] they had before. `None` when the backend can't tell without going through every cell
+    /// (see [`Engine::can_rewind`])
+    fn step_with_changes(&mut self, generations: u64) -> Option<Vec<((i64, i64), LifeState)>> {
+        self.step(generations);
+        None
+    }
+
+    /// Whether [`Engine::step_with_changes`] lists what changed, so [`crate::History`] can go back to the
+    /// exact same cells
+    fn can_rewind(&self) -> bool {
+        false
+    }
+
+    /// Births, deaths and changed cells of the last generation stepped (`None` if the backend doesn't count them)
+    fn last_step(&self) -> Option<StepStats> {
+        None
+    }
+
+    /// How many generations have been simulated so far
+    fn generation(&self) -> u128;
+
+    /// Overwrite the generation counter (used when going back in time with [`crate::History`]). Backends
+    /// counting in [`u64`] stop at [`u64::MAX`]
+    fn set_generation(&mut self, generation: u128);
+
+    /// How many cells are currently alive
+    fn population(&self) -> u64;
+
+    /// (x, y) of every alive cell, in no particular order
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (i64, i64)> + '_>;
+
+    /// (x, y) and [`LifeState`] of every cell that isn't dead, in no particular order
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), LifeState)> + '_> {
+        Box::new(self.live_cells().map(|pos| (pos, LifeState::Alive)))
+    }
+
+    /// Hash of every cell that isn't dead. Cells are hashed on their own and added up, so the order
+    /// [`Engine::cells`] lists them in doesn't matter
+    fn cells_hash(&self) -> u64 {
+        self.cells().fold(0, |hash, (pos, state)| {
+            hash.wrapping_add(cell_hash(pos, state))
+        })
+    }
+
+    /// Smallest (`min_x`, `min_y`, `max_x`, `max_y`) box holding every alive cell (`None` when empty)
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        self.live_cells().fold(None, |bb, (x, y)| match bb {
+            None => Some((x, y, x, y)),
+            Some((x0, y0, x1, y1)) => Some((x0.min(x), y0.min(y), x1.max(x), y1.max(y))),
+        })
+    }
+
+    /// Kill every cell
+    fn clear(&mut self);
+}
+
+/// Convert [`Engine`] coordinates to the [`i32`] ones bounded grids use (`None` if they can't fit)
+pub(crate) fn grid_coordinates(x: i64, y: i64) -> Option<(i32, i32)> {
+    Some((i32::try_from(x).ok()?, i32::try_from(y).ok()?))
+}
+
+/// Share of a single cell in [`Engine::cells_hash`] (dead cells have none)
+pub(crate) fn cell_hash((x, y): (i64, i64), state: LifeState) -> u64 {
+    match state {
+        LifeState::Dead => 0,
+        state => mix(mix(mix(x as u64) ^ y as u64) ^ state.index() as u64),
+    }
+}
+
+/// Cells of `before` (a snapshot of a bounded grid `columns` wide) that `after` changed, with their old
+/// [`LifeState`] as told by `state`
+pub(crate) fn grid_changes<T: PartialEq + Copy>(
+    before: &[T],
+    after: &[T],
+    columns: u32,
+    state: impl Fn(T) -> LifeState,
+) -> Vec<((i64, i64), LifeState)> {
+    let columns = columns.max(1) as usize;
+    before
+        .iter()
+        .zip(after)
+        .enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(i, (&old, _))| (((i % columns) as i64, (i / columns) as i64), state(old)))
+        .collect()
+}
+
+/// SplitMix64 finalizer: scrambles every bit of `z` into every bit of the result
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
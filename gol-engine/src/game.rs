@@ -0,0 +1,663 @@
+use crate::colours::Colours;
+use crate::engine::{grid_coordinates, Engine};
+use crate::kernel::Kernel;
+use crate::rule::Rule;
+use crate::soup::Rng;
+use crate::stats::StepStats;
+use crate::stochastic::Stochastic;
+use crate::tiling::Tiling;
+use crate::topology::Topology;
+use crate::update::Update;
+use std::collections::HashMap;
+
+/// [`LifeState`] is an enum indicating if [`Life`] is alive, dying or dead
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub enum LifeState {
+    /// Alive
+    Alive,
+    /// Refractory state of Generations rules. Holds how many generations it has been dying for (starting at 1)
+    Dying(u8),
+    /// Died
+    Dead,
+}
+
+impl LifeState {
+    /// State number as used by Generations rules (0 is dead, 1 is alive, 2 and up are dying).
+    /// Rule tables number their states the same way, so it doubles as a general state index
+    pub fn index(&self) -> u8 {
+        match self {
+            LifeState::Dead => 0,
+            LifeState::Alive => 1,
+            LifeState::Dying(k) => k + 1,
+        }
+    }
+
+    /// Inverse of [`LifeState::index`]
+    pub fn from_index(index: u8) -> Self {
+        match index {
+            0 => LifeState::Dead,
+            1 => LifeState::Alive,
+            k => LifeState::Dying(k - 1),
+        }
+    }
+}
+
+/// Struct representing each cube on screen (we call them [`Life`])
+#[derive(Clone, Copy, Debug)]
+pub struct Life {
+    /// X positon of the cube
+    pub x: i32,
+    /// Y position of the cube
+    pub y: i32,
+    /// Life state of the cube
+    pub state: LifeState,
+    /// Team of the cube while it's alive, from 1 to [`Colours::count`] (always 1 unless the [`Game`] has
+    /// [`Colours`])
+    pub colour: u8,
+}
+
+/// Salt of the [`Update`] rolls, after the ones of [`Stochastic`] so both can share a seed
+const UPDATE_SALT: u64 = 4;
+
+/// Main condition and logics happens here
+pub struct Game {
+    cubes: HashMap<(i32, i32), Life>,
+    cube_size: u32,
+    columns: u32,
+    rows: u32,
+    generation: u64,
+    rule: Rule,
+    /// Weighted neighborhood replacing the one of the [`Tiling`] of the rule
+    kernel: Option<Kernel>,
+    colours: Colours,
+    /// Chances rolled on top of the rule, if any
+    stochastic: Option<Stochastic>,
+    update: Update,
+    /// Seed of the random picks of [`Game::update`]
+    update_seed: u64,
+    topology: Topology,
+    threads: usize,
+    /// What the last generation changed
+    last_step: StepStats,
+    /// [`Engine`] state of every cell before its first change, while [`Engine::step_with_changes`] runs
+    recording: Option<HashMap<(i64, i64), LifeState>>,
+}
+
+impl Game {
+    /// Build a `columns` x `rows` grid of dead [`Life`]s, each one `cube_size` pixels wide
+    pub fn new(columns: u32, rows: u32, cube_size: u32) -> Self {
+        let mut cubes = HashMap::new();
+        for y in 0..rows as i32 {
+            for x in 0..columns as i32 {
+                cubes.insert(
+                    (x * cube_size as i32, y * cube_size as i32),
+                    Life {
+                        x: x * cube_size as i32,
+                        y: y * cube_size as i32,
+                        state: LifeState::Dead,
+                        colour: 1,
+                    },
+                );
+            }
+        }
+        Self {
+            cubes,
+            cube_size,
+            columns,
+            rows,
+            generation: 0,
+            rule: Rule::default(),
+            kernel: None,
+            colours: Colours::default(),
+            stochastic: None,
+            update: Update::default(),
+            update_seed: 0,
+            topology: Topology::default(),
+            threads: 1,
+            last_step: StepStats::default(),
+            recording: None,
+        }
+    }
+
+    /// How many cells there are on each row
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// How many rows there are in the grid
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// Size of each [`Life`] in pixels
+    pub fn cube_size(&self) -> u32 {
+        self.cube_size
+    }
+
+    /// [`Rule`] used to decide births and survivals (Conway's `B3/S23` by default)
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Replace the [`Rule`] used for the next generations
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// [`Kernel`] the neighbors are weighed with, if any
+    pub fn kernel(&self) -> Option<&Kernel> {
+        self.kernel.as_ref()
+    }
+
+    /// Weigh the neighbors with `kernel`: the [`Rule`] then decides on the sum of the weights of the alive
+    /// neighbors instead of how many there are (negative sums never give birth nor keep a cell alive).
+    /// `None` goes back to the neighbors of the [`Tiling`] of the rule
+    pub fn set_kernel(&mut self, kernel: Option<Kernel>) {
+        self.kernel = kernel;
+    }
+
+    /// Teams alive [`Life`]s are split between ([`Colours::Single`] by default)
+    pub fn colours(&self) -> Colours {
+        self.colours
+    }
+
+    /// Split alive [`Life`]s between the teams of `colours` (the ones in teams it doesn't have join team 1).
+    ///
+    /// Through [`Engine`], team `n` is the [`LifeState`] whose [`LifeState::index`] is `n`, so teams don't
+    /// mix with the dying states of Generations rules
+    pub fn set_colours(&mut self, colours: Colours) {
+        self.colours = colours;
+        for life in self.cubes.values_mut() {
+            if life.colour > colours.count() {
+                life.colour = 1;
+            }
+        }
+    }
+
+    /// Chances births, survivals and noise happen with, if the rule isn't followed to the letter
+    pub fn stochastic(&self) -> Option<Stochastic> {
+        self.stochastic
+    }
+
+    /// Roll the chances of `stochastic` for every [`Life`] of every generation. `None` goes back to
+    /// following the rule to the letter
+    pub fn set_stochastic(&mut self, stochastic: Option<Stochastic>) {
+        self.stochastic = stochastic;
+    }
+
+    /// Order cells are updated in during a generation ([`Update::Synchronous`] by default)
+    pub fn update(&self) -> Update {
+        self.update
+    }
+
+    /// Update cells in the order of `update`, with random picks seeded by `seed`. The picks only depend
+    /// on the seed and the generation, so going back in history then stepping again gives the same
+    /// generations
+    pub fn set_update(&mut self, update: Update, seed: u64) {
+        self.update = update;
+        self.update_seed = seed;
+    }
+
+    /// [`Topology`] deciding what's on the other side of the grid edges ([`Topology::Plane`] by default)
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Change how the grid edges are glued together
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// How many threads [`Game::apply_rules_to_each_lifes`] splits the grid between (sequential
+    /// [`Update`] schemes always use one)
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Step with `threads` threads, each one taking a share of the [`Life`]s. The result doesn't depend on it
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// How many generations have been simulated so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Births, deaths and changed [`Life`]s of the last generation
+    pub fn last_step(&self) -> StepStats {
+        self.last_step
+    }
+
+    /// [`LifeState`] of the cell at column `x` and row `y` (`None` if it's outside of the grid)
+    pub fn get_cell(&self, x: i32, y: i32) -> Option<LifeState> {
+        self.cubes
+            .get(&(x * self.cube_size as i32, y * self.cube_size as i32))
+            .map(|l| l.state)
+    }
+
+    /// Set the [`LifeState`] of the cell at column `x` and row `y`. Cells outside of the grid are ignored
+    pub fn set_cell(&mut self, x: i32, y: i32, state: LifeState) {
+        if let Some(life) = self
+            .cubes
+            .get_mut(&(x * self.cube_size as i32, y * self.cube_size as i32))
+        {
+            life.state = state;
+        }
+    }
+
+    /// Team of the cell at column `x` and row `y` (`None` if it isn't alive or is outside of the grid)
+    pub fn get_colour(&self, x: i32, y: i32) -> Option<u8> {
+        self.cubes
+            .get(&(x * self.cube_size as i32, y * self.cube_size as i32))
+            .filter(|l| l.state == LifeState::Alive)
+            .map(|l| l.colour)
+    }
+
+    /// Bring the cell at column `x` and row `y` to life in team `colour` (clamped to the teams of
+    /// [`Game::colours`]). Cells outside of the grid are ignored
+    pub fn set_colour(&mut self, x: i32, y: i32, colour: u8) {
+        let colour = colour.clamp(1, self.colours.count());
+        if let Some(life) = self
+            .cubes
+            .get_mut(&(x * self.cube_size as i32, y * self.cube_size as i32))
+        {
+            life.state = LifeState::Alive;
+            life.colour = colour;
+        }
+    }
+
+    /// Flip the cell at column `x` and row `y` between alive and dead (dying cells come back alive)
+    pub fn toggle_cell(&mut self, x: i32, y: i32) {
+        if let Some(state) = self.get_cell(x, y) {
+            self.set_cell(
+                x,
+                y,
+                if state == LifeState::Alive {
+                    LifeState::Dead
+                } else {
+                    LifeState::Alive
+                },
+            );
+        }
+    }
+
+    /// Iterate over every [`Life`] in the grid (in no particular order)
+    pub fn lifes(&self) -> impl Iterator<Item = &Life> {
+        self.cubes.values()
+    }
+
+    /// How many [`Life`]s are currently alive
+    pub fn population(&self) -> usize {
+        self.cubes
+            .values()
+            .filter(|l| l.state == LifeState::Alive)
+            .count()
+    }
+
+    /// Kill every [`Life`] in the grid
+    pub fn clear(&mut self) {
+        self.cubes.values_mut().for_each(|l| {
+            l.state = LifeState::Dead;
+        });
+    }
+
+    /// Advance the grid by one generation
+    pub fn step(&mut self) {
+        self.apply_rules_to_each_lifes();
+        self.generation += 1;
+    }
+
+    /// Apply each [`Life`] with new state base on conditions, in the order of the [`Update`] scheme
+    pub fn apply_rules_to_each_lifes(&mut self) {
+        if self.update.is_sequential() {
+            self.apply_rules_in_place();
+            return;
+        }
+        let lifes = self.cubes.values().collect::<Vec<_>>();
+        let new_states_of = |lifes: &[&Life]| {
+            lifes
+                .iter()
+                .filter_map(|life| {
+                    let skipped = match self.update {
+                        // each roll only depends on the cell, whichever share it's in
+                        Update::Alpha(alpha) => {
+                            let cube_size = self.cube_size as i32;
+                            Rng::for_cell(
+                                self.update_seed,
+                                self.generation,
+                                (life.x / cube_size, life.y / cube_size),
+                                UPDATE_SALT,
+                            )
+                            .next_f64()
+                                >= alpha
+                        }
+                        _ => false,
+                    };
+                    // without noise, nothing can happen to a skipped cell
+                    if skipped && self.stochastic.is_none_or(|s| s.noise == 0.0) {
+                        return None;
+                    }
+                    let (new_state, colour) = self.next_life(life, skipped);
+                    Some(((life.x, life.y), new_state, colour))
+                })
+                .collect::<Vec<_>>()
+        };
+        let apply_new_states = if self.threads <= 1 {
+            new_states_of(&lifes)
+        } else {
+            // new states only depend on the current ones, so every share can be worked out on its own
+            let share = lifes.len().div_ceil(self.threads).max(1);
+            std::thread::scope(|scope| {
+                let handles = lifes
+                    .chunks(share)
+                    .map(|lifes| scope.spawn(|| new_states_of(lifes)))
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .flat_map(|h| h.join().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        let mut stats = StepStats::default();
+        for (pos, new_state, colour) in apply_new_states {
+            self.remember(pos, new_state, colour);
+            if let Some(life) = self.cubes.get_mut(&pos) {
+                stats.record(life.state, new_state);
+                life.state = new_state;
+                life.colour = colour;
+            }
+        }
+        self.last_step = stats;
+    }
+
+    /// Update the [`Life`]s one at a time for [`Update::RandomSequential`] and [`Update::RandomOrder`], each
+    /// one seeing the ones updated before it. Runs on a single thread
+    fn apply_rules_in_place(&mut self) {
+        let cube_size = self.cube_size as i32;
+        // row by row, so the random picks don't depend on how the cubes are stored
+        let positions = (0..self.rows as i32)
+            .flat_map(|y| (0..self.columns as i32).map(move |x| (x * cube_size, y * cube_size)))
+            .collect::<Vec<_>>();
+        let mut rng = Rng::for_cell(self.update_seed, self.generation, (0, 0), UPDATE_SALT);
+        let order = match self.update {
+            Update::RandomOrder => {
+                // Fisher-Yates shuffle
+                let mut order = positions.clone();
+                for i in (1..order.len()).rev() {
+                    order.swap(i, (rng.next_u64() % (i as u64 + 1)) as usize);
+                }
+                order
+            }
+            _ => (0..positions.len())
+                .map(|_| positions[(rng.next_u64() % positions.len() as u64) as usize])
+                .collect(),
+        };
+        let before = positions
+            .iter()
+            .map(|pos| self.cubes[pos].state)
+            .collect::<Vec<_>>();
+        for pos in order {
+            let (new_state, colour) = self.next_life(&self.cubes[&pos], false);
+            self.remember(pos, new_state, colour);
+            if let Some(life) = self.cubes.get_mut(&pos) {
+                life.state = new_state;
+                life.colour = colour;
+            }
+        }
+
+        let mut stats = StepStats::default();
+        for (pos, old) in positions.iter().zip(before) {
+            stats.record(old, self.cubes[pos].state);
+        }
+        self.last_step = stats;
+    }
+
+    /// Note down the [`Engine`] state of the cube at `pos` (in pixels) if it's about to change to `state` and
+    /// `colour` for the first time since [`Engine::step_with_changes`] started
+    fn remember(&mut self, pos: (i32, i32), state: LifeState, colour: u8) {
+        let cube_size = self.cube_size as i32;
+        let old = match (&self.recording, self.cubes.get(&pos)) {
+            (Some(_), Some(life)) if (life.state, life.colour) != (state, colour) => {
+                self.engine_state(life)
+            }
+            _ => return,
+        };
+        if let Some(recording) = &mut self.recording {
+            let pos = ((pos.0 / cube_size) as i64, (pos.1 / cube_size) as i64);
+            recording.entry(pos).or_insert(old);
+        }
+    }
+
+    /// [`LifeState`] of the [`Life`] through [`Engine`], where every team of the [`Colours`] is a state
+    fn engine_state(&self, life: &Life) -> LifeState {
+        match life.state {
+            LifeState::Alive if self.colours != Colours::Single => {
+                LifeState::from_index(life.colour)
+            }
+            state => state,
+        }
+    }
+
+    /// [`LifeState`] and team the [`Life`] takes next from the cubes as they are now. A `skipped` one (left
+    /// out by [`Update::Alpha`]) keeps its state, only the noise of the [`Stochastic`] rolls can flip it
+    fn next_life(&self, life: &Life, skipped: bool) -> (LifeState, u8) {
+        let new_state = if skipped {
+            life.state
+        } else if let Some(kernel) = &self.kernel {
+            let sum = self.get_weighted_sum(life, kernel);
+            self.rule
+                .next_state(life.state, usize::try_from(sum).unwrap_or(usize::MAX))
+        } else if self.rule.tiling() == Tiling::Square {
+            self.rule
+                .next_state_of(life.state, self.get_neighborhood(life))
+        } else {
+            let alive_neighbors = self
+                .get_neighbors(life)
+                .iter()
+                .filter(|n| n.state == LifeState::Alive)
+                .count();
+            self.rule.next_state(life.state, alive_neighbors)
+        };
+        let new_state = match &self.stochastic {
+            Some(stochastic) => {
+                let cube_size = self.cube_size as i32;
+                let pos = (life.x / cube_size, life.y / cube_size);
+                if skipped {
+                    stochastic.noisy(&self.rule, self.generation, pos, new_state)
+                } else {
+                    stochastic.next_state(&self.rule, self.generation, pos, life.state, new_state)
+                }
+            }
+            None => new_state,
+        };
+        let colour = if self.colours != Colours::Single
+            && new_state == LifeState::Alive
+            && life.state != LifeState::Alive
+        {
+            let parents = self
+                .get_neighbors(life)
+                .iter()
+                .filter(|n| n.state == LifeState::Alive)
+                .map(|n| n.colour)
+                .collect::<Vec<_>>();
+            self.colours.newborn(&parents)
+        } else {
+            life.colour
+        };
+        (new_state, colour)
+    }
+
+    /// Alive cells of the 3x3 neighborhood around the [`Life`] as a [`Rule::lookup`] index (bit `y * 3 + x`
+    /// from the top left corner, the [`Life`] itself left out)
+    pub fn get_neighborhood(&self, life: &Life) -> usize {
+        let cube_size = self.cube_size as i32;
+        let mut neighborhood = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let nx = life.x / cube_size + dx;
+                let ny = life.y / cube_size + dy;
+                let alive = self
+                    .topology
+                    .wrap(nx, ny, self.columns as i32, self.rows as i32)
+                    .and_then(|(nx, ny)| self.cubes.get(&(nx * cube_size, ny * cube_size)))
+                    .is_some_and(|n| n.state == LifeState::Alive);
+                if alive {
+                    neighborhood |= 1 << ((dy + 1) * 3 + dx + 1);
+                }
+            }
+        }
+        neighborhood
+    }
+
+    /// Sum of the weights `kernel` gives to the alive neighbors of the [`Life`]
+    pub fn get_weighted_sum(&self, life: &Life, kernel: &Kernel) -> i64 {
+        kernel
+            .weights()
+            .iter()
+            .filter(|((dx, dy), _)| {
+                self.neighbor(life, *dx, *dy)
+                    .is_some_and(|n| n.state == LifeState::Alive)
+            })
+            .map(|(_, w)| *w as i64)
+            .sum()
+    }
+
+    /// Get neighbors around the [`Life`] (the ones of the [`Kernel`] if there's one, of the [`Tiling`] of the
+    /// rule otherwise)
+    pub fn get_neighbors(&self, life: &Life) -> Vec<Life> {
+        let cube_size = self.cube_size as i32;
+        let offsets: Vec<(i32, i32)> = match &self.kernel {
+            Some(kernel) => kernel.weights().iter().map(|(pos, _)| *pos).collect(),
+            None => self
+                .rule
+                .tiling()
+                .neighbors(life.x / cube_size, life.y / cube_size)
+                .to_vec(),
+        };
+        offsets
+            .into_iter()
+            .filter_map(|(dx, dy)| self.neighbor(life, dx, dy))
+            .collect()
+    }
+
+    /// [`Life`] `dx` columns and `dy` rows away from `life`. Past the edges it's either wrapped back into
+    /// the grid or `None`
+    fn neighbor(&self, life: &Life, dx: i32, dy: i32) -> Option<Life> {
+        let cube_size = self.cube_size as i32;
+        let (x, y) = (life.x / cube_size + dx, life.y / cube_size + dy);
+        let (nx, ny) = self
+            .topology
+            .wrap(x, y, self.columns as i32, self.rows as i32)?;
+        self.cubes.get(&(nx * cube_size, ny * cube_size)).copied()
+    }
+}
+
+impl Engine for Game {
+    fn name(&self) -> &'static str {
+        "game"
+    }
+
+    fn rule_name(&self) -> String {
+        self.rule.to_string()
+    }
+
+    fn states(&self) -> u8 {
+        match self.colours {
+            Colours::Single => self.rule.states(),
+            colours => colours.count() + 1,
+        }
+    }
+
+    fn topology(&self) -> Option<Topology> {
+        Some(self.topology)
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        let Some((x, y)) = grid_coordinates(x, y) else {
+            return LifeState::Dead;
+        };
+        match Game::get_colour(self, x, y) {
+            Some(colour) if self.colours != Colours::Single => LifeState::from_index(colour),
+            _ => Game::get_cell(self, x, y).unwrap_or(LifeState::Dead),
+        }
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        if let Some((x, y)) = grid_coordinates(x, y) {
+            if self.colours == Colours::Single || state == LifeState::Dead {
+                Game::set_cell(self, x, y, state);
+            } else {
+                Game::set_colour(self, x, y, state.index());
+            }
+        }
+    }
+
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            Game::step(self);
+        }
+    }
+
+    fn step_with_changes(&mut self, generations: u64) -> Option<Vec<((i64, i64), LifeState)>> {
+        self.recording = Some(HashMap::new());
+        Engine::step(self, generations);
+        self.recording.take().map(|changes| {
+            changes
+                .into_iter()
+                .filter(|&((x, y), old)| Engine::get_cell(self, x, y) != old)
+                .collect()
+        })
+    }
+
+    fn can_rewind(&self) -> bool {
+        true
+    }
+
+    fn last_step(&self) -> Option<StepStats> {
+        Some(self.last_step)
+    }
+
+    fn generation(&self) -> u128 {
+        self.generation as u128
+    }
+
+    fn set_generation(&mut self, generation: u128) {
+        self.generation = u64::try_from(generation).unwrap_or(u64::MAX);
+    }
+
+    fn population(&self) -> u64 {
+        Game::population(self) as u64
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (i64, i64)> + '_> {
+        let cube_size = self.cube_size as i32;
+        Box::new(
+            self.cubes
+                .values()
+                .filter(|l| l.state == LifeState::Alive)
+                .map(move |l| ((l.x / cube_size) as i64, (l.y / cube_size) as i64)),
+        )
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), LifeState)> + '_> {
+        let cube_size = self.cube_size as i32;
+        Box::new(
+            self.cubes
+                .values()
+                .filter(|l| l.state != LifeState::Dead)
+                .map(move |l| {
+                    let pos = ((l.x / cube_size) as i64, (l.y / cube_size) as i64);
+                    (pos, self.engine_state(l))
+                }),
+        )
+    }
+
+    fn clear(&mut self) {
+        Game::clear(self);
+    }
+}
@@ -0,0 +1,512 @@
+//! HashLife: quadtree of hash-consed nodes with memoised futures, for jumping huge amounts of generations
+use crate::engine::Engine;
+use crate::game::LifeState;
+use crate::rule::Rule;
+use std::collections::HashMap;
+
+/// Index of a node in [`HashLifeGame::nodes`]. `0` and `1` are the dead and alive cells
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// Once there are this many nodes the cache is thrown away and only the current pattern is kept
+const GARBAGE_COLLECTION_THRESHOLD: usize = 1 << 23;
+
+/// Largest step exponent allowed, so the quadtree still fits in [`i64`] coordinates
+pub const MAX_STEP_EXPONENT: u8 = 56;
+
+/// One square of the quadtree, `2^level` cells wide
+#[derive(Clone, Copy)]
+struct Node {
+    /// North west, north east, south west and south east quarters
+    children: [NodeId; 4],
+    level: u8,
+    population: u64,
+}
+
+/// Unbounded universe stepped with Bill Gosper's HashLife algorithm.
+///
+/// Identical squares anywhere in space and time are stored once, and the future of each square is
+/// remembered, so regular patterns (guns, breeders, spaceships) can be stepped `2^k` generations at a time.
+/// Like [`crate::SparseGame`], `B0` rules and Generations rules aren't supported (dying cells count as dead).
+/// Cells are always squares (the [`crate::Tiling`] of the rule is ignored)
+///
+/// ```
+/// use gol_engine::{HashLifeGame, LifeState};
+///
+/// let mut game = HashLifeGame::new();
+/// for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+///     game.set_cell(x, y, LifeState::Alive);
+/// }
+/// game.set_step_exponent(40);
+/// game.step();
+/// assert_eq!(game.generation(), 1 << 40);
+/// assert_eq!(game.population(), 5);
+/// assert_eq!(game.get_cell(1 << 38, (1 << 38) + 2), LifeState::Alive);
+/// ```
+pub struct HashLifeGame {
+    nodes: Vec<Node>,
+    /// Canonical node for each set of children (hash-consing)
+    lookup: HashMap<[NodeId; 4], NodeId>,
+    /// Center of a node `2^j` generations later, keyed by (node, `j`)
+    memo: HashMap<(NodeId, u8), NodeId>,
+    /// Empty node of each level
+    empty: Vec<NodeId>,
+    root: NodeId,
+    /// Coordinates of the north west corner of the root
+    origin: (i64, i64),
+    generation: u128,
+    rule: Rule,
+    step_exponent: u8,
+}
+
+impl Default for HashLifeGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HashLifeGame {
+    /// Empty universe running Conway's `B3/S23`, stepping 1 generation at a time
+    pub fn new() -> Self {
+        let mut game = Self {
+            nodes: vec![
+                Node {
+                    children: [DEAD; 4],
+                    level: 0,
+                    population: 0,
+                },
+                Node {
+                    children: [DEAD; 4],
+                    level: 0,
+                    population: 1,
+                },
+            ],
+            lookup: HashMap::new(),
+            memo: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: (-4, -4),
+            generation: 0,
+            rule: Rule::default(),
+            step_exponent: 0,
+        };
+        game.root = game.empty_node(3);
+        game
+    }
+
+    /// [`Rule`] used to decide births and survivals
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Replace the [`Rule`] used for the next generations
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.memo.clear();
+    }
+
+    /// Each [`HashLifeGame::step`] advances `2^step_exponent` generations
+    pub fn step_exponent(&self) -> u8 {
+        self.step_exponent
+    }
+
+    /// Make each [`HashLifeGame::step`] advance `2^exponent` generations (capped at [`MAX_STEP_EXPONENT`])
+    pub fn set_step_exponent(&mut self, exponent: u8) {
+        self.step_exponent = exponent.min(MAX_STEP_EXPONENT);
+    }
+
+    /// How many generations have been simulated so far
+    pub fn generation(&self) -> u128 {
+        self.generation
+    }
+
+    /// How many cells are currently alive
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+
+    fn level(&self, node: NodeId) -> u8 {
+        self.nodes[node as usize].level
+    }
+
+    fn children(&self, node: NodeId) -> [NodeId; 4] {
+        self.nodes[node as usize].children
+    }
+
+    /// Canonical node made of the 4 given quarters
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let children = [nw, ne, sw, se];
+        if let Some(&id) = self.lookup.get(&children) {
+            return id;
+        }
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            children,
+            level: self.level(nw) + 1,
+            population: children
+                .iter()
+                .map(|&c| self.nodes[c as usize].population)
+                .sum(),
+        });
+        self.lookup.insert(children, id);
+        id
+    }
+
+    fn empty_node(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let bigger = self.join(e, e, e, e);
+            self.empty.push(bigger);
+        }
+        self.empty[level as usize]
+    }
+
+    /// Twice as big root with the current one in the middle
+    fn expand(&mut self) {
+        let level = self.level(self.root);
+        let e = self.empty_node(level - 1);
+        let [nw, ne, sw, se] = self.children(self.root);
+        let nw = self.join(e, e, e, nw);
+        let ne = self.join(e, e, ne, e);
+        let sw = self.join(e, sw, e, e);
+        let se = self.join(se, e, e, e);
+        self.root = self.join(nw, ne, sw, se);
+        let half = 1i64 << (level - 1);
+        self.origin = (self.origin.0 - half, self.origin.1 - half);
+    }
+
+    fn root_contains(&self, x: i64, y: i64) -> bool {
+        let size = 1i64 << self.level(self.root);
+        (self.origin.0..self.origin.0 + size).contains(&x)
+            && (self.origin.1..self.origin.1 + size).contains(&y)
+    }
+
+    /// [`LifeState`] of the cell at (`x`, `y`)
+    pub fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        if !self.root_contains(x, y) {
+            return LifeState::Dead;
+        }
+        let (mut x, mut y) = (x - self.origin.0, y - self.origin.1);
+        let mut node = self.root;
+        while self.level(node) > 0 {
+            if self.nodes[node as usize].population == 0 {
+                return LifeState::Dead;
+            }
+            let half = 1i64 << (self.level(node) - 1);
+            let quarter = (x >= half) as usize + 2 * (y >= half) as usize;
+            node = self.children(node)[quarter];
+            x %= half;
+            y %= half;
+        }
+        if node == ALIVE {
+            LifeState::Alive
+        } else {
+            LifeState::Dead
+        }
+    }
+
+    fn set_in(&mut self, node: NodeId, x: i64, y: i64, cell: NodeId) -> NodeId {
+        let level = self.level(node);
+        if level == 0 {
+            return cell;
+        }
+        let half = 1i64 << (level - 1);
+        let quarter = (x >= half) as usize + 2 * (y >= half) as usize;
+        let mut children = self.children(node);
+        children[quarter] = self.set_in(children[quarter], x % half, y % half, cell);
+        let [nw, ne, sw, se] = children;
+        self.join(nw, ne, sw, se)
+    }
+
+    /// Set the [`LifeState`] of the cell at (`x`, `y`)
+    pub fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        while !self.root_contains(x, y) {
+            self.expand();
+        }
+        let cell = if state == LifeState::Alive {
+            ALIVE
+        } else {
+            DEAD
+        };
+        self.root = self.set_in(self.root, x - self.origin.0, y - self.origin.1, cell);
+        // every edit leaves a path of nodes behind, so drawing a lot between steps fills the table too
+        self.collect_garbage_if_full();
+    }
+
+    /// Flip the cell at (`x`, `y`) between alive and dead
+    pub fn toggle_cell(&mut self, x: i64, y: i64) {
+        let state = if self.get_cell(x, y) == LifeState::Alive {
+            LifeState::Dead
+        } else {
+            LifeState::Alive
+        };
+        self.set_cell(x, y, state);
+    }
+
+    /// Kill every cell in the universe
+    pub fn clear(&mut self) {
+        let level = self.level(self.root);
+        self.root = self.empty_node(level);
+    }
+
+    /// Visit every alive cell under `node` (whose north west corner is at (`x`, `y`))
+    fn visit(&self, node: NodeId, x: i64, y: i64, f: &mut impl FnMut(i64, i64)) {
+        let n = &self.nodes[node as usize];
+        if n.population == 0 {
+            return;
+        }
+        if n.level == 0 {
+            f(x, y);
+            return;
+        }
+        let half = 1i64 << (n.level - 1);
+        for (i, &c) in n.children.iter().enumerate() {
+            self.visit(c, x + half * (i % 2) as i64, y + half * (i / 2) as i64, f);
+        }
+    }
+
+    /// (x, y) of every alive cell
+    pub fn alive_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = Vec::with_capacity(self.population() as usize);
+        self.visit(self.root, self.origin.0, self.origin.1, &mut |x, y| {
+            cells.push((x, y))
+        });
+        cells
+    }
+
+    /// Smallest (`min_x`, `min_y`, `max_x`, `max_y`) box holding every alive cell (`None` when empty)
+    pub fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        let mut bb: Option<(i64, i64, i64, i64)> = None;
+        self.visit(self.root, self.origin.0, self.origin.1, &mut |x, y| {
+            bb = Some(match bb {
+                None => (x, y, x, y),
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+            })
+        });
+        bb
+    }
+
+    /// Center 2x2 of a 4x4 node one generation later
+    fn step_4x4(&mut self, node: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (i, &quarter) in self.children(node).iter().enumerate() {
+            for (j, &cell) in self.children(quarter).iter().enumerate() {
+                cells[2 * (i / 2) + j / 2][2 * (i % 2) + j % 2] = cell == ALIVE;
+            }
+        }
+        let mut next = [DEAD; 4];
+        for (i, n) in next.iter_mut().enumerate() {
+            let (y, x) = (1 + i / 2, 1 + i % 2);
+            let mut neighborhood = 0;
+            for dy in 0..3 {
+                for dx in 0..3 {
+                    if cells[y + dy - 1][x + dx - 1] {
+                        neighborhood |= 1 << (dy * 3 + dx);
+                    }
+                }
+            }
+            *n = if self.rule.lookup(neighborhood) {
+                ALIVE
+            } else {
+                DEAD
+            };
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// Center quarter of `node`, `2^j` generations later (`j` is at most `level - 2`)
+    fn successor(&mut self, node: NodeId, j: u8) -> NodeId {
+        let n = self.nodes[node as usize];
+        let j = j.min(n.level - 2);
+        if n.population == 0 {
+            return self.empty_node(n.level - 1);
+        }
+        if let Some(&s) = self.memo.get(&(node, j)) {
+            return s;
+        }
+        let s = if n.level == 2 {
+            self.step_4x4(node)
+        } else {
+            let [a, b, c, d] = n.children;
+            let [_, ab, ac, ad] = self.children(a);
+            let [ba, _, bc, bd] = self.children(b);
+            let [ca, cb, _, cd] = self.children(c);
+            let [da, db, dc, _] = self.children(d);
+            // 9 overlapping squares half as big as `node`, stepped forward
+            let n2 = self.join(ab, ba, ad, bc);
+            let n4 = self.join(ac, ad, ca, cb);
+            let n5 = self.join(ad, bc, cb, da);
+            let n6 = self.join(bc, bd, da, db);
+            let n8 = self.join(cb, da, cd, dc);
+            let c1 = self.successor(a, j);
+            let c2 = self.successor(n2, j);
+            let c3 = self.successor(b, j);
+            let c4 = self.successor(n4, j);
+            let c5 = self.successor(n5, j);
+            let c6 = self.successor(n6, j);
+            let c7 = self.successor(c, j);
+            let c8 = self.successor(n8, j);
+            let c9 = self.successor(d, j);
+            if j < n.level - 2 {
+                // already 2^j generations ahead, just stitch the centers together
+                let [c1, c2, c3, c4, c5, c6, c7, c8, c9] =
+                    [c1, c2, c3, c4, c5, c6, c7, c8, c9].map(|c| self.children(c));
+                let nw = self.join(c1[3], c2[2], c4[1], c5[0]);
+                let ne = self.join(c2[3], c3[2], c5[1], c6[0]);
+                let sw = self.join(c4[3], c5[2], c7[1], c8[0]);
+                let se = self.join(c5[3], c6[2], c8[1], c9[0]);
+                self.join(nw, ne, sw, se)
+            } else {
+                // halfway there, step the 4 quarters the other half
+                let nw = self.join(c1, c2, c4, c5);
+                let ne = self.join(c2, c3, c5, c6);
+                let sw = self.join(c4, c5, c7, c8);
+                let se = self.join(c5, c6, c8, c9);
+                let nw = self.successor(nw, j);
+                let ne = self.successor(ne, j);
+                let sw = self.successor(sw, j);
+                let se = self.successor(se, j);
+                self.join(nw, ne, sw, se)
+            }
+        };
+        self.memo.insert((node, j), s);
+        s
+    }
+
+    /// Is every alive cell inside the middle square half as wide as the root?
+    fn centered(&self) -> bool {
+        let [a, b, c, d] = self.children(self.root);
+        let pop = |id: NodeId| self.nodes[id as usize].population;
+        let inner = [(a, 3), (b, 2), (c, 1), (d, 0)]
+            .iter()
+            .map(|&(q, i)| {
+                let sub = self.children(q)[i];
+                pop(self.children(sub)[i])
+            })
+            .sum::<u64>();
+        inner == self.population()
+    }
+
+    /// Throw away the cache and every node the current pattern doesn't use
+    fn collect_garbage(&mut self) {
+        let mut fresh = HashLifeGame::new();
+        fresh.rule = self.rule;
+        fresh.step_exponent = self.step_exponent;
+        fresh.generation = self.generation;
+        let mut copied = HashMap::new();
+        fresh.root = fresh.copy_from(self, self.root, &mut copied);
+        fresh.origin = self.origin;
+        *self = fresh;
+    }
+
+    /// [`HashLifeGame::collect_garbage`] once the node table grows past [`GARBAGE_COLLECTION_THRESHOLD`]
+    fn collect_garbage_if_full(&mut self) {
+        if self.nodes.len() > GARBAGE_COLLECTION_THRESHOLD {
+            self.collect_garbage();
+        }
+    }
+
+    fn copy_from(
+        &mut self,
+        other: &HashLifeGame,
+        node: NodeId,
+        copied: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if node <= ALIVE {
+            return node;
+        }
+        if let Some(&id) = copied.get(&node) {
+            return id;
+        }
+        let [nw, ne, sw, se] = other
+            .children(node)
+            .map(|c| self.copy_from(other, c, copied));
+        let id = self.join(nw, ne, sw, se);
+        copied.insert(node, id);
+        id
+    }
+
+    /// Advance the universe by `2^step_exponent` generations
+    pub fn step(&mut self) {
+        self.step_pow2(self.step_exponent);
+    }
+
+    /// Advance the universe by `2^j` generations
+    fn step_pow2(&mut self, j: u8) {
+        // pad the pattern so nothing it does in 2^j generations can reach past the result
+        while self.level(self.root) < j + 3 || !self.centered() {
+            self.expand();
+        }
+        let level = self.level(self.root);
+        self.root = self.successor(self.root, j);
+        let quarter = 1i64 << (level - 2);
+        self.origin = (self.origin.0 + quarter, self.origin.1 + quarter);
+        self.generation += 1 << j;
+        self.collect_garbage_if_full();
+    }
+}
+
+impl Engine for HashLifeGame {
+    fn name(&self) -> &'static str {
+        "hashlife"
+    }
+
+    fn rule_name(&self) -> String {
+        self.rule.to_string()
+    }
+
+    /// Dying states are dropped, so there's only alive and dead
+    fn states(&self) -> u8 {
+        2
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        HashLifeGame::get_cell(self, x, y)
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        HashLifeGame::set_cell(self, x, y, state);
+    }
+
+    /// Jumps one power of two at a time, biggest first
+    fn step(&mut self, generations: u64) {
+        for j in (0..64).rev() {
+            if generations & (1 << j) == 0 {
+                continue;
+            }
+            if j <= MAX_STEP_EXPONENT {
+                self.step_pow2(j);
+            } else {
+                for _ in 0..1 << (j - MAX_STEP_EXPONENT) {
+                    self.step_pow2(MAX_STEP_EXPONENT);
+                }
+            }
+        }
+    }
+
+    fn generation(&self) -> u128 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u128) {
+        self.generation = generation;
+    }
+
+    fn population(&self) -> u64 {
+        HashLifeGame::population(self)
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (i64, i64)> + '_> {
+        Box::new(self.alive_cells().into_iter())
+    }
+
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        HashLifeGame::bounding_box(self)
+    }
+
+    fn clear(&mut self) {
+        HashLifeGame::clear(self);
+    }
+}
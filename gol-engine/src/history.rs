@@ -0,0 +1,134 @@
+//! Bounded record of past generations, for stepping backwards
+use crate::engine::Engine;
+use crate::game::LifeState;
+use std::collections::VecDeque;
+
+/// What one step changed: the cells it touched with the state they had before it
+struct Change {
+    generation: u128,
+    cells: Vec<((i64, i64), LifeState)>,
+}
+
+impl Change {
+    /// Rough amount of memory the change holds on to
+    fn size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.cells.capacity() * std::mem::size_of::<((i64, i64), LifeState)>()
+    }
+}
+
+/// Ring buffer of the changes made by the last steps of an [`Engine`].
+///
+/// Only the cells each step changed are kept, so still lifes and empty space cost nothing, and backends list
+/// them as they step (see [`Engine::step_with_changes`]). Once the changes take more than the memory cap the
+/// oldest ones are thrown away
+///
+/// ```
+/// use gol_engine::{Engine, History, LifeState, SparseGame};
+///
+/// let mut game = SparseGame::new();
+/// for (x, y) in [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)] {
+///     game.set_cell(x, y, LifeState::Alive);
+/// }
+/// let mut start: Vec<_> = game.cells().collect();
+/// start.sort();
+/// let mut history = History::new(1 << 20);
+/// for _ in 0..100 {
+///     history.step(&mut game, 1);
+/// }
+/// while history.rewind(&mut game) {}
+/// assert_eq!(Engine::generation(&game), 0);
+/// let mut now: Vec<_> = game.cells().collect();
+/// now.sort();
+/// assert_eq!(now, start);
+/// ```
+pub struct History {
+    changes: VecDeque<Change>,
+    /// Memory used by `changes`, in bytes
+    size: usize,
+    /// Most memory `changes` may use, in bytes
+    capacity: usize,
+}
+
+impl History {
+    /// Empty history that never holds more than about `capacity` bytes (`0` turns it off)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            changes: VecDeque::new(),
+            size: 0,
+            capacity,
+        }
+    }
+
+    /// How many steps can be rewound
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Whether there's nothing left to rewind
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Memory currently used, in bytes
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Most memory the history may use, in bytes
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Forget everything (call it after editing cells by hand, older changes wouldn't fit anymore)
+    pub fn clear(&mut self) {
+        self.changes.clear();
+        self.size = 0;
+    }
+
+    /// Step `engine` by `generations` and remember what changed. Backends that can't list their changes
+    /// (see [`Engine::can_rewind`]) are stepped without it, and everything before can't be rewound anymore
+    pub fn step<E: Engine + ?Sized>(&mut self, engine: &mut E, generations: u64) {
+        if self.capacity == 0 || !engine.can_rewind() {
+            engine.step(generations);
+            self.clear();
+            return;
+        }
+        let generation = engine.generation();
+        match engine.step_with_changes(generations) {
+            Some(cells) => self.record(generation, cells),
+            None => self.clear(),
+        }
+    }
+
+    /// Remember that a step from `generation` changed `cells` (with the [`LifeState`] they had before, as
+    /// listed by [`Engine::step_with_changes`])
+    pub fn record(&mut self, generation: u128, mut cells: Vec<((i64, i64), LifeState)>) {
+        if self.capacity == 0 {
+            return;
+        }
+        cells.shrink_to_fit();
+        let change = Change { generation, cells };
+        self.size += change.size();
+        self.changes.push_back(change);
+        while self.size > self.capacity {
+            match self.changes.pop_front() {
+                Some(oldest) => self.size -= oldest.size(),
+                None => break,
+            }
+        }
+    }
+
+    /// Undo the last recorded step of `engine`. Returns `false` when there's nothing left to undo
+    pub fn rewind<E: Engine + ?Sized>(&mut self, engine: &mut E) -> bool {
+        let Some(change) = self.changes.pop_back() else {
+            return false;
+        };
+        self.size -= change.size();
+        for &((x, y), state) in &change.cells {
+            engine.set_cell(x, y, state);
+        }
+        engine.set_generation(change.generation);
+        true
+    }
+}
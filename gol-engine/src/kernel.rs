@@ -0,0 +1,174 @@
+//! Weighted neighborhoods ("weighted life") for [`crate::Game`]
+use std::{fmt, str::FromStr};
+
+/// Which cells around a cell count as its neighbors, and how much each one of them is worth.
+///
+/// With a kernel, [`crate::Game`] adds up the weights of the alive neighbors instead of counting them,
+/// and the [`crate::Rule`] decides on that sum. Kernels are written as a square of integers centered on the
+/// cell itself, one row per line (`#` starts a comment):
+/// ```
+/// use gol_engine::Kernel;
+///
+/// let kernel: Kernel = "# corners are worth less than sides
+/// 1 2 1
+/// 2 0 2
+/// 1 2 1".parse().unwrap();
+/// assert_eq!(kernel.max_sum(), 12);
+/// assert_eq!(kernel.weights().len(), 8);
+/// assert_eq!(kernel.to_string(), "1 2 1\n2 0 2\n1 2 1\n");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Kernel {
+    /// (`dx`, `dy`) and weight of every neighbor whose weight isn't 0, row by row
+    weights: Vec<((i32, i32), i32)>,
+}
+
+impl Kernel {
+    /// Largest distance a kernel reaches on either axis
+    pub const MAX_RANGE: i32 = 50;
+
+    /// Kernel out of (`dx`, `dy`) offsets and their weights. Zero weights, the cell itself and
+    /// offsets past [`Kernel::MAX_RANGE`] are dropped
+    pub fn new(weights: impl IntoIterator<Item = ((i32, i32), i32)>) -> Self {
+        let mut weights = weights
+            .into_iter()
+            .filter(|&((dx, dy), w)| {
+                w != 0
+                    && (dx, dy) != (0, 0)
+                    && dx.abs() <= Self::MAX_RANGE
+                    && dy.abs() <= Self::MAX_RANGE
+            })
+            .collect::<Vec<_>>();
+        weights.sort_by_key(|&((dx, dy), _)| (dy, dx));
+        Self { weights }
+    }
+
+    /// The 8 cells around, each worth 1 (what Life-like rules use)
+    pub fn moore() -> Self {
+        Self::new(
+            (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| ((dx, dy), 1)))
+                .filter(|&(pos, _)| pos != (0, 0)),
+        )
+    }
+
+    /// The 4 cells sharing a side, each worth 1
+    pub fn von_neumann() -> Self {
+        Self::new([((0, -1), 1), ((-1, 0), 1), ((1, 0), 1), ((0, 1), 1)])
+    }
+
+    /// The 6 neighbors of a hexagon on the skewed square grid [`crate::Tiling::Hexagonal`] uses, each worth 1
+    pub fn hexagonal() -> Self {
+        Self::new(
+            Self::moore()
+                .weights
+                .into_iter()
+                .filter(|&(pos, _)| pos != (1, -1) && pos != (-1, 1)),
+        )
+    }
+
+    /// (`dx`, `dy`) and weight of every neighbor (none of them are 0), row by row
+    pub fn weights(&self) -> &[((i32, i32), i32)] {
+        &self.weights
+    }
+
+    /// Largest sum of weights a cell can get (every neighbor with a positive weight alive)
+    pub fn max_sum(&self) -> i32 {
+        self.weights.iter().map(|(_, w)| w.max(&0)).sum()
+    }
+
+    /// How far the kernel reaches on either axis
+    pub fn range(&self) -> i32 {
+        self.weights
+            .iter()
+            .map(|((dx, dy), _)| dx.abs().max(dy.abs()))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Error returned when a kernel can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKernelError(String);
+
+impl fmt::Display for ParseKernelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid kernel: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseKernelError {}
+
+impl FromStr for Kernel {
+    type Err = ParseKernelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rows = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let row = line
+                .split_whitespace()
+                .map(|w| {
+                    w.parse::<i32>().map_err(|_| {
+                        ParseKernelError(format!("`{}` on line {} isn't a weight", w, i + 1))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(row);
+        }
+        let size = rows.len();
+        if size % 2 == 0 {
+            return Err(ParseKernelError(format!(
+                "it has {} rows, it needs an odd amount of them to have a middle",
+                size
+            )));
+        }
+        if size > (2 * Self::MAX_RANGE + 1) as usize {
+            return Err(ParseKernelError(format!(
+                "it's {} rows high, the most is {}",
+                size,
+                2 * Self::MAX_RANGE + 1
+            )));
+        }
+        if let Some(row) = rows.iter().find(|row| row.len() != size) {
+            return Err(ParseKernelError(format!(
+                "a row has {} weights, every row needs {} of them",
+                row.len(),
+                size
+            )));
+        }
+        let r = (size / 2) as i32;
+        if rows[r as usize][r as usize] != 0 {
+            return Err(ParseKernelError(
+                "the middle is the cell itself, its weight has to be 0".to_string(),
+            ));
+        }
+        Ok(Self::new(rows.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, &w)| ((x as i32 - r, y as i32 - r), w))
+        })))
+    }
+}
+
+impl fmt::Display for Kernel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let r = self.range();
+        for dy in -r..=r {
+            let row = (-r..=r)
+                .map(|dx| {
+                    self.weights
+                        .iter()
+                        .find(|(pos, _)| *pos == (dx, dy))
+                        .map_or(0, |(_, w)| *w)
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,449 @@
+//! Lenia: continuous cells holding a value from 0 to 1, grown by a smooth ring kernel
+use crate::engine::{grid_coordinates, Engine};
+use crate::game::LifeState;
+use crate::rule::ParseRuleError;
+use crate::topology::Topology;
+use std::{fmt, str::FromStr};
+
+/// Growth function turning the kernel sum `u` of a cell into how fast its value goes up (1) or down (-1)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Growth {
+    /// `2 exp(-(u - mu)² / 2 sigma²) - 1`, the one Lenia uses by default
+    Gaussian,
+    /// `2 max(0, 1 - (u - mu)² / 9 sigma²)⁴ - 1`, like the gaussian one but flat past `3 sigma`
+    Polynomial,
+    /// `1` when `u` is at most `sigma` away from `mu`, `-1` otherwise (hard edges, like SmoothLife)
+    Step,
+}
+
+impl Growth {
+    /// Every growth function, in the order they're listed in `--help`
+    pub const ALL: [Growth; 3] = [Growth::Gaussian, Growth::Polynomial, Growth::Step];
+
+    /// Name used in rulestrings
+    pub fn name(&self) -> &'static str {
+        match self {
+            Growth::Gaussian => "gaussian",
+            Growth::Polynomial => "polynomial",
+            Growth::Step => "step",
+        }
+    }
+}
+
+/// Lenia rule: kernel radius and rings, growth function and time step (`R13,b1,m0.15,s0.015,dt0.1,gaussian`
+/// is the one of the Orbium glider)
+///
+/// ```
+/// use gol_engine::{Growth, LeniaRule};
+///
+/// let orbium: LeniaRule = "R13,m0.15,s0.015,dt0.1".parse().unwrap();
+/// assert_eq!(orbium, LeniaRule::orbium());
+/// assert_eq!(orbium.growth_function(), Growth::Gaussian);
+/// assert_eq!(orbium.growth(0.15), 1.0);
+/// assert!(orbium.growth(0.5) < -0.99);
+/// assert_eq!(orbium.to_string(), "R13,b1,m0.15,s0.015,dt0.1,gaussian");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeniaRule {
+    radius: u8,
+    /// Height of each ring of the kernel, from the inside out
+    peaks: Vec<f32>,
+    mu: f32,
+    sigma: f32,
+    dt: f32,
+    growth: Growth,
+}
+
+impl LeniaRule {
+    /// Largest radius accepted, the kernel already covers about 8000 cells there
+    pub const MAX_RADIUS: u8 = 50;
+
+    /// Orbium unicaudatus, Lenia's best known glider: a single ring of radius 13
+    pub fn orbium() -> Self {
+        Self {
+            radius: 13,
+            peaks: vec![1.0],
+            mu: 0.15,
+            sigma: 0.015,
+            dt: 0.1,
+            growth: Growth::Gaussian,
+        }
+    }
+
+    /// How many cells out the kernel reaches
+    pub fn radius(&self) -> u8 {
+        self.radius
+    }
+
+    /// Height of each ring of the kernel, from the inside out
+    pub fn peaks(&self) -> &[f32] {
+        &self.peaks
+    }
+
+    /// Kernel sum growing the fastest
+    pub fn mu(&self) -> f32 {
+        self.mu
+    }
+
+    /// How far from `mu` kernel sums still make cells grow
+    pub fn sigma(&self) -> f32 {
+        self.sigma
+    }
+
+    /// Fraction of the growth applied each generation
+    pub fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    /// [`Growth`] function turning kernel sums into growth (see [`LeniaRule::growth`])
+    pub fn growth_function(&self) -> Growth {
+        self.growth
+    }
+
+    /// Growth (from -1 to 1) of a cell whose kernel sum is `u`
+    pub fn growth(&self, u: f32) -> f32 {
+        let d = (u - self.mu) / self.sigma;
+        match self.growth {
+            Growth::Gaussian => 2.0 * (-d * d / 2.0).exp() - 1.0,
+            Growth::Polynomial => 2.0 * (1.0 - d * d / 9.0).max(0.0).powi(4) - 1.0,
+            Growth::Step if d.abs() <= 1.0 => 1.0,
+            Growth::Step => -1.0,
+        }
+    }
+
+    /// (`dx`, `dy`) and weight of every cell of the ring kernel. The weights add up to 1
+    pub fn kernel(&self) -> Vec<((i32, i32), f32)> {
+        let r = self.radius as i32;
+        // smooth bump going from 0 at both ends to 1 in the middle
+        let bump = |x: f32| {
+            if x > 0.0 && x < 1.0 {
+                (4.0 - 1.0 / (x * (1.0 - x))).exp()
+            } else {
+                0.0
+            }
+        };
+        let rings = self.peaks.len() as f32;
+        let mut kernel = (-r..=r)
+            .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+            .filter_map(|(dx, dy)| {
+                let distance = ((dx * dx + dy * dy) as f32).sqrt() / r as f32 * rings;
+                let ring = distance.floor() as usize;
+                let weight = self.peaks.get(ring)? * bump(distance.fract());
+                (weight > 0.0).then_some(((dx, dy), weight))
+            })
+            .collect::<Vec<_>>();
+        let total: f32 = kernel.iter().map(|(_, w)| w).sum();
+        for (_, w) in &mut kernel {
+            *w /= total;
+        }
+        kernel
+    }
+}
+
+impl Default for LeniaRule {
+    fn default() -> Self {
+        Self::orbium()
+    }
+}
+
+impl FromStr for LeniaRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let error = |what: &str| ParseRuleError(format!("{} in `{}`", what, s));
+        let number = |n: &str| {
+            n.parse::<f32>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .ok_or_else(|| error(&format!("`{}` isn't a number", n)))
+        };
+        let (mut radius, mut peaks, mut mu, mut sigma) = (None, vec![1.0], None, None);
+        let (mut dt, mut growth) = (0.1, Growth::Gaussian);
+        for part in s.split(',') {
+            let part = part.trim();
+            if let Some(g) = Growth::ALL.into_iter().find(|g| g.name() == part) {
+                growth = g;
+            } else if let Some(value) = part.strip_prefix("dt") {
+                dt = number(value)?;
+                if !(dt > 0.0 && dt <= 1.0) {
+                    return Err(error("dt has to be more than 0 and at most 1"));
+                }
+            } else if let Some(value) = part.strip_prefix('R') {
+                radius = match value.parse::<u8>() {
+                    Ok(r) if (1..=Self::MAX_RADIUS).contains(&r) => Some(r),
+                    _ => {
+                        return Err(error(&format!(
+                            "the radius has to be 1 to {}",
+                            Self::MAX_RADIUS
+                        )))
+                    }
+                };
+            } else if let Some(value) = part.strip_prefix('b') {
+                peaks = value.split('/').map(number).collect::<Result<_, _>>()?;
+                if peaks.iter().any(|p| !(0.0..=1.0).contains(p)) || peaks.iter().all(|&p| p == 0.0)
+                {
+                    return Err(error(
+                        "ring heights go from 0 to 1, and at least one of them has to be more than 0",
+                    ));
+                }
+            } else if let Some(value) = part.strip_prefix('m') {
+                mu = Some(number(value)?);
+            } else if let Some(value) = part.strip_prefix('s') {
+                sigma = Some(number(value)?.abs()).filter(|&s| s > 0.0);
+                if sigma.is_none() {
+                    return Err(error("sigma can't be 0"));
+                }
+            } else {
+                return Err(error(&format!("unknown part `{}`", part)));
+            }
+        }
+        Ok(Self {
+            radius: radius.ok_or_else(|| error("missing the radius (`R<n>`)"))?,
+            peaks,
+            mu: mu.ok_or_else(|| error("missing the growth centre (`m<mu>`)"))?,
+            sigma: sigma.ok_or_else(|| error("missing the growth width (`s<sigma>`)"))?,
+            dt,
+            growth,
+        })
+    }
+}
+
+impl fmt::Display for LeniaRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "R{},b{},m{},s{},dt{},{}",
+            self.radius,
+            self.peaks
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join("/"),
+            self.mu,
+            self.sigma,
+            self.dt,
+            self.growth.name()
+        )
+    }
+}
+
+/// Bounded grid running a [`LeniaRule`].
+///
+/// Each step pads the grid with `R` ghost cells from the [`Topology`] on every side, adds up the kernel
+/// over it, and moves every cell `dt` times its growth, clamped to 0..1.
+/// Through [`Engine`], values are rounded to 255 levels: 0 is [`LifeState::Dead`], 1 is
+/// [`LifeState::Alive`] and `k / 254` in between is [`LifeState::Dying`]`(k)`
+pub struct LeniaGame {
+    columns: u32,
+    rows: u32,
+    /// Value of every cell, row by row
+    cells: Vec<f32>,
+    generation: u64,
+    rule: LeniaRule,
+    /// [`LeniaRule::kernel`], cached
+    kernel: Vec<((i32, i32), f32)>,
+    topology: Topology,
+    /// Grid with its ghost cells, reused between steps
+    padded: Vec<f32>,
+}
+
+/// Levels between 0 and 1 values are rounded to through [`Engine`]
+const LEVELS: f32 = 254.0;
+
+impl LeniaGame {
+    /// Build a `columns` x `rows` grid of cells at 0 running `rule`
+    pub fn new(columns: u32, rows: u32, rule: LeniaRule) -> Self {
+        Self {
+            columns,
+            rows,
+            cells: vec![0.0; columns as usize * rows as usize],
+            generation: 0,
+            kernel: rule.kernel(),
+            rule,
+            topology: Topology::default(),
+            padded: Vec::new(),
+        }
+    }
+
+    /// How many cells there are on each row
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// How many rows there are in the grid
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// [`LeniaRule`] deciding how cells grow
+    pub fn rule(&self) -> &LeniaRule {
+        &self.rule
+    }
+
+    /// Replace the [`LeniaRule`] used for the next generations
+    pub fn set_rule(&mut self, rule: LeniaRule) {
+        self.kernel = rule.kernel();
+        self.rule = rule;
+    }
+
+    /// [`Topology`] deciding what's on the other side of the grid edges
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Change how the grid edges are glued together
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// How many generations have been simulated so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if (0..self.columns as i32).contains(&x) && (0..self.rows as i32).contains(&y) {
+            Some(y as usize * self.columns as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Value of the cell at column `x` and row `y` (`None` if it's outside of the grid)
+    pub fn get_cell(&self, x: i32, y: i32) -> Option<f32> {
+        self.index(x, y).map(|i| self.cells[i])
+    }
+
+    /// Set the value of the cell at column `x` and row `y`, clamped to 0..1. Cells outside of the grid are ignored
+    pub fn set_cell(&mut self, x: i32, y: i32, value: f32) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = value.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Sum of the values of every cell
+    pub fn mass(&self) -> f32 {
+        self.cells.iter().sum()
+    }
+
+    /// Put every cell back to 0
+    pub fn clear(&mut self) {
+        self.cells.fill(0.0);
+    }
+
+    /// Advance the grid by one generation
+    pub fn step(&mut self) {
+        let r = self.rule.radius as i32;
+        let (columns, rows) = (self.columns as i32, self.rows as i32);
+        let padded_w = columns + 2 * r;
+        let mut padded = std::mem::take(&mut self.padded);
+        padded.clear();
+        for py in 0..rows + 2 * r {
+            for px in 0..padded_w {
+                padded.push(
+                    self.topology
+                        .wrap(px - r, py - r, columns, rows)
+                        .and_then(|(x, y)| self.index(x, y))
+                        .map_or(0.0, |i| self.cells[i]),
+                );
+            }
+        }
+        // kernel offsets as distances in the padded grid
+        let kernel = self
+            .kernel
+            .iter()
+            .map(|&((dx, dy), w)| ((dy * padded_w + dx) as isize, w))
+            .collect::<Vec<_>>();
+        for y in 0..rows {
+            for x in 0..columns {
+                let middle = ((y + r) * padded_w + x + r) as isize;
+                let u: f32 = kernel
+                    .iter()
+                    .map(|&(offset, w)| w * padded[(middle + offset) as usize])
+                    .sum();
+                let cell = &mut self.cells[(y * columns + x) as usize];
+                *cell = (*cell + self.rule.dt * self.rule.growth(u)).clamp(0.0, 1.0);
+            }
+        }
+        self.padded = padded;
+        self.generation += 1;
+    }
+}
+
+impl Engine for LeniaGame {
+    fn name(&self) -> &'static str {
+        "lenia"
+    }
+
+    fn rule_name(&self) -> String {
+        self.rule.to_string()
+    }
+
+    fn states(&self) -> u8 {
+        LEVELS as u8 + 1
+    }
+
+    fn topology(&self) -> Option<Topology> {
+        Some(self.topology)
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        match Engine::value(self, x, y).map_or(0, |v| (v * LEVELS).round() as u8) {
+            0 => LifeState::Dead,
+            k if k as f32 == LEVELS => LifeState::Alive,
+            k => LifeState::Dying(k),
+        }
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        let value = match state {
+            LifeState::Dead => 0.0,
+            LifeState::Alive => 1.0,
+            LifeState::Dying(k) => k as f32 / LEVELS,
+        };
+        if let Some((x, y)) = grid_coordinates(x, y) {
+            LeniaGame::set_cell(self, x, y, value);
+        }
+    }
+
+    fn value(&self, x: i64, y: i64) -> Option<f32> {
+        grid_coordinates(x, y).and_then(|(x, y)| LeniaGame::get_cell(self, x, y))
+    }
+
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            LeniaGame::step(self);
+        }
+    }
+
+    fn generation(&self) -> u128 {
+        self.generation as u128
+    }
+
+    fn set_generation(&mut self, generation: u128) {
+        self.generation = u64::try_from(generation).unwrap_or(u64::MAX);
+    }
+
+    fn population(&self) -> u64 {
+        self.cells().count() as u64
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (i64, i64)> + '_> {
+        Box::new(self.cells().map(|(pos, _)| pos))
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), LifeState)> + '_> {
+        let columns = self.columns as usize;
+        Box::new(
+            (0..self.cells.len())
+                .map(move |i| ((i % columns) as i64, (i / columns) as i64))
+                .map(|(x, y)| ((x, y), Engine::get_cell(self, x, y)))
+                .filter(|(_, state)| *state != LifeState::Dead),
+        )
+    }
+
+    fn clear(&mut self) {
+        LeniaGame::clear(self);
+    }
+}
@@ -0,0 +1,58 @@
+//! Headless engine behind timelessnesses' implementation of Conway's Game Of Life.
+//!
+//! Nothing in here touches SDL2, so batch tools and tests can link it without vcpkg.
+//!
+//! ```
+//! use gol_engine::{Game, LifeState};
+//!
+//! let mut game = Game::new(5, 5, 1);
+//! for x in 1..4 {
+//!     game.set_cell(x, 2, LifeState::Alive);
+//! }
+//! game.step();
+//! assert_eq!(game.get_cell(2, 1), Some(LifeState::Alive));
+//! assert_eq!(game.population(), 3);
+//! ```
+pub mod colours;
+pub mod cycle;
+pub mod dense;
+pub mod elementary;
+pub mod engine;
+pub mod game;
+pub mod hashlife;
+pub mod history;
+pub mod kernel;
+pub mod lenia;
+pub mod ltl;
+pub mod rule;
+pub mod soup;
+pub mod sparse;
+pub mod stats;
+pub mod stochastic;
+pub mod symmetry;
+pub mod table;
+pub mod tiling;
+pub mod topology;
+pub mod update;
+
+pub use colours::{Colours, ParseColoursError};
+pub use cycle::{Cycle, CycleDetector};
+pub use dense::DenseGame;
+pub use elementary::{ElementaryGame, ElementaryRule};
+pub use engine::Engine;
+pub use game::{Game, Life, LifeState};
+pub use hashlife::HashLifeGame;
+pub use history::History;
+pub use kernel::{Kernel, ParseKernelError};
+pub use lenia::{Growth, LeniaGame, LeniaRule};
+pub use ltl::{LtlGame, LtlRule, Neighborhood};
+pub use rule::{ParseRuleError, Rule};
+pub use soup::{Rng, Soup};
+pub use sparse::SparseGame;
+pub use stats::{GenerationStats, StatsFormat, StatsWriter, StepStats};
+pub use stochastic::Stochastic;
+pub use symmetry::{ParseSymmetryError, Symmetry};
+pub use table::{ParseTableError, RuleTable, TableGame};
+pub use tiling::Tiling;
+pub use topology::{ParseTopologyError, Topology};
+pub use update::{ParseUpdateError, Update};
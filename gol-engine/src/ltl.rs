@@ -0,0 +1,455 @@
+//! Larger than Life: range-R neighborhoods with birth/survival intervals, counted with summed-area tables
+use crate::engine::{grid_changes, grid_coordinates, Engine};
+use crate::game::LifeState;
+use crate::rule::{dying, ParseRuleError};
+use crate::topology::Topology;
+use std::ops::RangeInclusive;
+use std::{fmt, str::FromStr};
+
+/// Shape of a Larger than Life neighborhood (rule tables only go 1 cell out)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// Every cell in the `(2R + 1)` x `(2R + 1)` square (`NM`, `Moore` in rule tables)
+    Moore,
+    /// Every cell at most `R` steps away without going diagonally, a diamond (`NN`, `vonNeumann` in rule tables)
+    VonNeumann,
+}
+
+/// Larger than Life rule in Golly's notation (`R5,C0,M1,S34..58,B34..45,NM` is Bosco's Rule)
+///
+/// ```
+/// use gol_engine::LtlRule;
+///
+/// let bosco: LtlRule = "R5,C0,M1,S34..58,B34..45,NM".parse().unwrap();
+/// assert_eq!(bosco.range(), 5);
+/// assert!(bosco.survives(34) && !bosco.survives(59));
+/// assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LtlRule {
+    range: u8,
+    states: u8,
+    /// Whether a cell counts itself
+    middle: bool,
+    survival: RangeInclusive<u32>,
+    birth: RangeInclusive<u32>,
+    neighborhood: Neighborhood,
+}
+
+impl LtlRule {
+    /// Largest range accepted, to keep the count tables a sane size
+    pub const MAX_RANGE: u8 = 50;
+
+    /// How many cells out the neighborhood reaches
+    pub fn range(&self) -> u8 {
+        self.range
+    }
+
+    /// Total amount of states a cell can be in (2 unless it's a Generations style rule)
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Shape of the neighborhood, a square or a diamond reaching [`LtlRule::range`] cells out
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// Should a dead cell with `count` alive cells in its neighborhood be born?
+    pub fn born(&self, count: u32) -> bool {
+        self.birth.contains(&count)
+    }
+
+    /// Should an alive cell with `count` alive cells in its neighborhood (itself included with `M1`) stay alive?
+    pub fn survives(&self, count: u32) -> bool {
+        self.survival.contains(&count)
+    }
+
+    /// [`LifeState`] a cell in `state` ends up in next generation
+    pub fn next_state(&self, state: LifeState, count: u32) -> LifeState {
+        match state {
+            LifeState::Alive if self.survives(count) => LifeState::Alive,
+            LifeState::Alive => dying(self.states, 1),
+            LifeState::Dead if self.born(count) => LifeState::Alive,
+            LifeState::Dead => LifeState::Dead,
+            LifeState::Dying(k) => dying(self.states, k + 1),
+        }
+    }
+}
+
+impl FromStr for LtlRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let error = |what: &str| ParseRuleError(format!("{} in `{}`", what, s));
+        let number = |n: &str| {
+            n.parse::<u32>()
+                .map_err(|_| error(&format!("`{}` isn't a number", n)))
+        };
+        let interval = |i: &str| -> Result<RangeInclusive<u32>, ParseRuleError> {
+            match i.split_once("..") {
+                Some((low, high)) => Ok(number(low)?..=number(high)?),
+                None => Ok(number(i)?..=number(i)?),
+            }
+        };
+        let (mut range, mut states, mut middle) = (None, 2, false);
+        let (mut survival, mut birth, mut neighborhood) = (None, None, Neighborhood::Moore);
+        for part in s.split(',') {
+            let part = part.trim().to_ascii_uppercase();
+            let (key, value) = part.split_at(part.len().min(1));
+            match key {
+                "R" => range = Some(number(value)?),
+                "C" => states = number(value)?.max(2),
+                "M" => middle = number(value)? == 1,
+                "S" => survival = Some(interval(value)?),
+                "B" => birth = Some(interval(value)?),
+                "N" => {
+                    neighborhood = match value {
+                        "M" => Neighborhood::Moore,
+                        "N" => Neighborhood::VonNeumann,
+                        _ => {
+                            return Err(error(&format!(
+                                "unknown neighborhood `N{}` (NM or NN)",
+                                value
+                            )))
+                        }
+                    }
+                }
+                _ => return Err(error(&format!("unknown part `{}`", part))),
+            }
+        }
+        let range = match range {
+            Some(r) if (1..=Self::MAX_RANGE as u32).contains(&r) => r as u8,
+            Some(_) => {
+                return Err(error(&format!(
+                    "the range has to be 1 to {}",
+                    Self::MAX_RANGE
+                )))
+            }
+            None => return Err(error("missing the range (`R<n>`)")),
+        };
+        if states > 255 {
+            return Err(error("too many states (255 at most)"));
+        }
+        Ok(Self {
+            range,
+            states: states as u8,
+            middle,
+            survival: survival
+                .ok_or_else(|| error("missing the survival interval (`S<a>..<b>`)"))?,
+            birth: birth.ok_or_else(|| error("missing the birth interval (`B<a>..<b>`)"))?,
+            neighborhood,
+        })
+    }
+}
+
+impl fmt::Display for LtlRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},{}",
+            self.range,
+            if self.states > 2 { self.states } else { 0 },
+            self.middle as u8,
+            self.survival.start(),
+            self.survival.end(),
+            self.birth.start(),
+            self.birth.end(),
+            match self.neighborhood {
+                Neighborhood::Moore => "NM",
+                Neighborhood::VonNeumann => "NN",
+            }
+        )
+    }
+}
+
+/// Bounded grid running a [`LtlRule`].
+///
+/// Each step pads the alive cells with `R` ghost cells from the [`Topology`] on every side and builds a
+/// summed-area table of them, so any neighborhood count takes 4 lookups whatever the range.
+/// Von Neumann diamonds are squares once the grid is turned 45 degrees, so they get a table of the turned grid
+pub struct LtlGame {
+    columns: u32,
+    rows: u32,
+    /// [`LifeState::index`] of every cell, row by row
+    cells: Vec<u8>,
+    generation: u64,
+    rule: LtlRule,
+    topology: Topology,
+    /// Summed-area table reused between steps
+    table: Vec<u32>,
+}
+
+impl LtlGame {
+    /// Build a `columns` x `rows` grid of dead cells running `rule`
+    pub fn new(columns: u32, rows: u32, rule: LtlRule) -> Self {
+        Self {
+            columns,
+            rows,
+            cells: vec![0; columns as usize * rows as usize],
+            generation: 0,
+            rule,
+            topology: Topology::default(),
+            table: Vec::new(),
+        }
+    }
+
+    /// How many cells there are on each row
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// How many rows there are in the grid
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// [`LtlRule`] deciding births and survivals
+    pub fn rule(&self) -> &LtlRule {
+        &self.rule
+    }
+
+    /// Replace the [`LtlRule`] used for the next generations
+    pub fn set_rule(&mut self, rule: LtlRule) {
+        self.rule = rule;
+    }
+
+    /// [`Topology`] deciding what's on the other side of the grid edges
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Change how the grid edges are glued together
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// How many generations have been simulated so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if (0..self.columns as i32).contains(&x) && (0..self.rows as i32).contains(&y) {
+            Some(y as usize * self.columns as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// [`LifeState`] of the cell at column `x` and row `y` (`None` if it's outside of the grid)
+    pub fn get_cell(&self, x: i32, y: i32) -> Option<LifeState> {
+        self.index(x, y)
+            .map(|i| LifeState::from_index(self.cells[i]))
+    }
+
+    /// Set the [`LifeState`] of the cell at column `x` and row `y`. Cells outside of the grid are ignored
+    pub fn set_cell(&mut self, x: i32, y: i32, state: LifeState) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = state.index();
+        }
+    }
+
+    /// How many cells are currently alive
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&c| c == 1).count()
+    }
+
+    /// Kill every cell in the grid
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+    }
+
+    /// Is the cell at column `x` and row `y` alive once the edges are wrapped by the topology?
+    fn alive_at(&self, x: i32, y: i32) -> bool {
+        self.topology
+            .wrap(x, y, self.columns as i32, self.rows as i32)
+            .and_then(|(x, y)| self.index(x, y))
+            .is_some_and(|i| self.cells[i] == 1)
+    }
+
+    /// Advance the grid by one generation
+    pub fn step(&mut self) {
+        let r = self.rule.range as i32;
+        let (columns, rows) = (self.columns as i32, self.rows as i32);
+        // the grid with `r` ghost cells on every side
+        let (padded_w, padded_h) = (columns + 2 * r, rows + 2 * r);
+        let mut table = std::mem::take(&mut self.table);
+        let count: Box<dyn Fn(i32, i32) -> u32 + '_> = match self.rule.neighborhood {
+            Neighborhood::Moore => {
+                let w = padded_w as usize + 1;
+                table.clear();
+                table.resize(w * (padded_h as usize + 1), 0);
+                for py in 0..padded_h {
+                    let mut row_sum = 0;
+                    for px in 0..padded_w {
+                        row_sum += self.alive_at(px - r, py - r) as u32;
+                        let i = (py as usize + 1) * w + px as usize + 1;
+                        table[i] = table[i - w] + row_sum;
+                    }
+                }
+                let table = &table;
+                // square from (x - r, y - r) to (x + r, y + r), which is (x, y) to (x + 2r, y + 2r) padded
+                Box::new(move |x, y| {
+                    let (x0, y0, x1, y1) = (
+                        x as usize,
+                        y as usize,
+                        (x + 2 * r + 1) as usize,
+                        (y + 2 * r + 1) as usize,
+                    );
+                    table[y1 * w + x1] + table[y0 * w + x0]
+                        - table[y0 * w + x1]
+                        - table[y1 * w + x0]
+                })
+            }
+            Neighborhood::VonNeumann => {
+                // padded (px, py) goes to (u, v) = (px + py, px - py + padded_h - 1)
+                let size = (padded_w + padded_h - 1) as usize;
+                let w = size + 1;
+                table.clear();
+                table.resize(w * w, 0);
+                for py in 0..padded_h {
+                    for px in 0..padded_w {
+                        if self.alive_at(px - r, py - r) {
+                            let (u, v) = ((px + py) as usize, (px - py + padded_h - 1) as usize);
+                            table[(v + 1) * w + u + 1] = 1;
+                        }
+                    }
+                }
+                for v in 1..w {
+                    let mut row_sum = 0;
+                    for u in 1..w {
+                        row_sum += table[v * w + u];
+                        table[v * w + u] = table[(v - 1) * w + u] + row_sum;
+                    }
+                }
+                let table = &table;
+                Box::new(move |x, y| {
+                    let (px, py) = (x + r, y + r);
+                    let (u, v) = (px + py, px - py + padded_h - 1);
+                    let (u0, v0, u1, v1) = (
+                        (u - r) as usize,
+                        (v - r) as usize,
+                        (u + r + 1) as usize,
+                        (v + r + 1) as usize,
+                    );
+                    table[v1 * w + u1] + table[v0 * w + u0]
+                        - table[v0 * w + u1]
+                        - table[v1 * w + u0]
+                })
+            }
+        };
+        let next: Vec<u8> = (0..rows)
+            .flat_map(|y| (0..columns).map(move |x| (x, y)))
+            .zip(&self.cells)
+            .map(|((x, y), &state)| {
+                let mut n = count(x, y);
+                if !self.rule.middle && state == 1 {
+                    n -= 1;
+                }
+                self.rule
+                    .next_state(LifeState::from_index(state), n)
+                    .index()
+            })
+            .collect();
+        drop(count);
+        self.table = table;
+        self.cells = next;
+        self.generation += 1;
+    }
+}
+
+impl Engine for LtlGame {
+    fn name(&self) -> &'static str {
+        "ltl"
+    }
+
+    fn rule_name(&self) -> String {
+        self.rule.to_string()
+    }
+
+    fn states(&self) -> u8 {
+        self.rule.states
+    }
+
+    fn topology(&self) -> Option<Topology> {
+        Some(self.topology)
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        grid_coordinates(x, y)
+            .and_then(|(x, y)| LtlGame::get_cell(self, x, y))
+            .unwrap_or(LifeState::Dead)
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        if let Some((x, y)) = grid_coordinates(x, y) {
+            LtlGame::set_cell(self, x, y, state);
+        }
+    }
+
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            LtlGame::step(self);
+        }
+    }
+
+    fn step_with_changes(&mut self, generations: u64) -> Option<Vec<((i64, i64), LifeState)>> {
+        let before = self.cells.clone();
+        Engine::step(self, generations);
+        Some(grid_changes(
+            &before,
+            &self.cells,
+            self.columns,
+            LifeState::from_index,
+        ))
+    }
+
+    fn can_rewind(&self) -> bool {
+        true
+    }
+
+    fn generation(&self) -> u128 {
+        self.generation as u128
+    }
+
+    fn set_generation(&mut self, generation: u128) {
+        self.generation = u64::try_from(generation).unwrap_or(u64::MAX);
+    }
+
+    fn population(&self) -> u64 {
+        LtlGame::population(self) as u64
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (i64, i64)> + '_> {
+        let columns = self.columns as usize;
+        Box::new(
+            self.cells
+                .iter()
+                .enumerate()
+                .filter(|(_, &c)| c == 1)
+                .map(move |(i, _)| ((i % columns) as i64, (i / columns) as i64)),
+        )
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), LifeState)> + '_> {
+        let columns = self.columns as usize;
+        Box::new(
+            self.cells
+                .iter()
+                .enumerate()
+                .filter(|(_, &c)| c != 0)
+                .map(move |(i, &c)| {
+                    (
+                        ((i % columns) as i64, (i / columns) as i64),
+                        LifeState::from_index(c),
+                    )
+                }),
+        )
+    }
+
+    fn clear(&mut self) {
+        LtlGame::clear(self);
+    }
+}
@@ -0,0 +1,569 @@
+//! Birth/survival rules for Life-like automatons (`B3/S23` is Conway's)
+use crate::game::LifeState;
+use crate::tiling::Tiling;
+use std::{fmt, str::FromStr};
+
+/// A Life-like rule stored as a lookup table over every 3x3 neighborhood, so isotropic non-totalistic
+/// rules (Hensel notation, `B2-a/S12`) work just like the usual totalistic ones
+///
+/// Parses the usual rulestring notations, including the `/C<n>` suffix of Generations rules:
+/// ```
+/// use gol_engine::Rule;
+///
+/// let highlife: Rule = "B36/S23".parse().unwrap();
+/// assert_eq!("23/36".parse::<Rule>().unwrap(), highlife);
+/// assert_eq!(highlife.to_string(), "B36/S23");
+/// assert!("B2/S".parse::<Rule>().is_ok());
+///
+/// let star_wars: Rule = "345/2/4".parse().unwrap();
+/// assert_eq!(star_wars.to_string(), "B2/S345/C4");
+/// assert_eq!(star_wars.states(), 4);
+///
+/// let tlife: Rule = "B3/S2-i34q".parse().unwrap();
+/// assert!(!tlife.is_totalistic());
+/// assert_eq!(tlife.to_string(), "B3/S2-i34q");
+/// ```
+///
+/// Rules for the other [`Tiling`]s end with their suffix. They only count neighbors, up to 6 on hexagons
+/// and up to 12 on triangles (written `a`, `b` and `c` past 9):
+/// ```
+/// use gol_engine::{Rule, Tiling};
+///
+/// let hex: Rule = "B2/S34H".parse().unwrap();
+/// assert_eq!(hex.tiling(), Tiling::Hexagonal);
+/// assert_eq!("B4/S9abL".parse::<Rule>().unwrap().to_string(), "B4/S9abL");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// Bit `i` is set when a cell whose 3x3 neighborhood is `i` is alive next generation (see [`Rule::lookup`])
+    table: [u64; 8],
+    /// Bit `n` is set when every arrangement of `n` alive neighbors gives birth to a dead cell
+    birth: u64,
+    /// Bit `n` is set when every arrangement of `n` alive neighbors keeps an alive cell alive
+    survival: u64,
+    states: u8,
+    tiling: Tiling,
+}
+
+impl Default for Rule {
+    /// Conway's Game Of Life (`B3/S23`)
+    fn default() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+}
+
+/// Bit of the cell itself in a 3x3 neighborhood
+const CENTER: usize = 1 << 4;
+
+/// Every bit of a 3x3 neighborhood but the cell itself
+const NEIGHBORS: usize = 0b111_101_111;
+
+/// Hensel letters of the neighbor counts up to 4, each with one neighborhood of its class. Counts 5 to 7
+/// use the letters of `8 - n`, standing for the neighborhoods where alive and dead neighbors are swapped
+const LETTERS: [&[(char, usize)]; 5] = [
+    &[],
+    &[('c', 1), ('e', 2)],
+    &[
+        ('c', 5),
+        ('e', 10),
+        ('a', 3),
+        ('i', 40),
+        ('k', 33),
+        ('n', 68),
+    ],
+    &[
+        ('c', 69),
+        ('e', 42),
+        ('a', 11),
+        ('i', 7),
+        ('k', 98),
+        ('n', 13),
+        ('j', 14),
+        ('q', 70),
+        ('r', 41),
+        ('y', 97),
+    ],
+    &[
+        ('c', 325),
+        ('e', 170),
+        ('a', 15),
+        ('i', 45),
+        ('k', 99),
+        ('n', 71),
+        ('j', 106),
+        ('q', 102),
+        ('r', 43),
+        ('t', 101),
+        ('w', 105),
+        ('y', 78),
+        ('z', 108),
+    ],
+];
+
+/// Hensel letters `count` alive neighbors can be arranged in (none for 0 and 8)
+fn letters(count: usize) -> impl Iterator<Item = char> {
+    LETTERS[count.min(8 - count)]
+        .iter()
+        .map(|(letter, _)| *letter)
+}
+
+/// Every neighborhood (without the cell itself) with `count` alive neighbors arranged like `letter`
+/// (any arrangement with no letter). `None` when `count` has no such letter
+fn neighborhoods(count: usize, letter: Option<char>) -> Option<Vec<usize>> {
+    let Some(letter) = letter else {
+        return Some(
+            (0..512)
+                .filter(|i| i & CENTER == 0 && i.count_ones() as usize == count)
+                .collect(),
+        );
+    };
+    let (_, first) = LETTERS[count.min(8 - count)]
+        .iter()
+        .find(|(l, _)| *l == letter)?;
+    let first = if count > 4 { first ^ NEIGHBORS } else { *first };
+    // move the neighbors around: bit y * 3 + x goes to the bit `to(x, y)` gives
+    let map = |i: usize, to: fn(usize, usize) -> (usize, usize)| {
+        (0..9)
+            .filter(|b| i & (1 << b) != 0)
+            .map(|b| {
+                let (x, y) = to(b % 3, b / 3);
+                1 << (y * 3 + x)
+            })
+            .sum::<usize>()
+    };
+    let mut class = Vec::new();
+    let mut i = first;
+    for _ in 0..4 {
+        i = map(i, |x, y| (2 - y, x));
+        class.push(i);
+        class.push(map(i, |x, y| (2 - x, y)));
+    }
+    class.sort_unstable();
+    class.dedup();
+    Some(class)
+}
+
+/// Make `neighborhood` alive in a lookup table
+fn set(table: &mut [u64; 8], neighborhood: usize) {
+    table[neighborhood >> 6] |= 1 << (neighborhood & 63);
+}
+
+impl Rule {
+    /// Build a rule from the neighbor counts that give birth to a dead cell and keep an alive cell alive.
+    /// Counts past 8 only matter to [`crate::Kernel`]s (sums of weights), and 64 and more are ignored
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        let mask = |counts: &[u8]| {
+            counts
+                .iter()
+                .filter(|&&n| n < 64)
+                .fold(0, |mask, n| mask | (1 << n))
+        };
+        Self::from_masks(mask(birth), mask(survival))
+    }
+
+    /// Square rule out of bitmasks of neighbor counts (or sums)
+    fn from_masks(birth: u64, survival: u64) -> Self {
+        let mut table = [0; 8];
+        for (mask, center) in [(birth, 0), (survival, CENTER)] {
+            for n in (0..=8).filter(|n| mask & (1 << n) != 0) {
+                for i in neighborhoods(n, None).unwrap() {
+                    set(&mut table, i | center);
+                }
+            }
+        }
+        let mut rule = Self::from_table(table);
+        rule.birth |= birth;
+        rule.survival |= survival;
+        rule
+    }
+
+    /// Rule of a lookup table (as described in [`Rule::lookup`])
+    fn from_table(table: [u64; 8]) -> Self {
+        let mut rule = Self {
+            table,
+            birth: 0,
+            survival: 0,
+            states: 2,
+            tiling: Tiling::Square,
+        };
+        for n in 0..=8 {
+            let neighborhoods = neighborhoods(n, None).unwrap();
+            if neighborhoods.iter().all(|&i| rule.lookup(i)) {
+                rule.birth |= 1 << n;
+            }
+            if neighborhoods.iter().all(|&i| rule.lookup(i | CENTER)) {
+                rule.survival |= 1 << n;
+            }
+        }
+        rule
+    }
+
+    /// Totalistic rule of a [`Tiling`] other than squares, from bitmasks of neighbor counts
+    fn from_counts(birth: u64, survival: u64, tiling: Tiling) -> Self {
+        Self {
+            table: [0; 8],
+            birth,
+            survival,
+            states: 2,
+            tiling,
+        }
+    }
+
+    /// Turn this into a Generations rule with `states` states in total (dead, alive and `states - 2` dying states)
+    pub fn with_states(self, states: u8) -> Self {
+        Self {
+            states: states.max(2),
+            ..self
+        }
+    }
+
+    /// Total amount of states a cell can be in (2 for plain Life-like rules)
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Shape of the cells this rule is meant for
+    pub fn tiling(&self) -> Tiling {
+        self.tiling
+    }
+
+    /// Is a cell alive next generation when its 3x3 neighborhood is `neighborhood`? Bit `y * 3 + x` of it
+    /// is the cell at (`x`, `y`) from the top left corner, the cell itself being bit 4. Only
+    /// [`Tiling::Square`] rules fill this table
+    pub fn lookup(&self, neighborhood: usize) -> bool {
+        self.table[neighborhood >> 6 & 7] & (1 << (neighborhood & 63)) != 0
+    }
+
+    /// Do births and survivals only depend on how many neighbors are alive (not on where they are)?
+    pub fn is_totalistic(&self) -> bool {
+        if self.tiling != Tiling::Square {
+            return true;
+        }
+        let counts = |mask: u64| {
+            (0..=8)
+                .filter(|n| mask & (1 << n) != 0)
+                .collect::<Vec<u8>>()
+        };
+        Self::new(&counts(self.birth), &counts(self.survival)).table == self.table
+    }
+
+    /// Should a dead cell with `alive_neighbors` alive neighbors be born? With non-totalistic rules,
+    /// that's only when it happens whatever the arrangement of those neighbors
+    pub fn born(&self, alive_neighbors: usize) -> bool {
+        alive_neighbors < 64 && self.birth & (1 << alive_neighbors) != 0
+    }
+
+    /// Should an alive cell with `alive_neighbors` alive neighbors stay alive? With non-totalistic rules,
+    /// that's only when it happens whatever the arrangement of those neighbors
+    pub fn survives(&self, alive_neighbors: usize) -> bool {
+        alive_neighbors < 64 && self.survival & (1 << alive_neighbors) != 0
+    }
+
+    /// [`LifeState`] a cell in `state` ends up in next generation. Only [`LifeState::Alive`] neighbors count
+    pub fn next_state(&self, state: LifeState, alive_neighbors: usize) -> LifeState {
+        match state {
+            LifeState::Alive if self.survives(alive_neighbors) => LifeState::Alive,
+            LifeState::Alive => self.dying(1),
+            LifeState::Dead if self.born(alive_neighbors) => LifeState::Alive,
+            LifeState::Dead => LifeState::Dead,
+            LifeState::Dying(k) => self.dying(k + 1),
+        }
+    }
+
+    /// Same as [`Rule::next_state`] with the alive neighbors given as a 3x3 neighborhood (see [`Rule::lookup`],
+    /// the bit of the cell itself is ignored)
+    pub fn next_state_of(&self, state: LifeState, neighborhood: usize) -> LifeState {
+        let neighborhood = neighborhood & NEIGHBORS;
+        match state {
+            LifeState::Alive if self.lookup(neighborhood | CENTER) => LifeState::Alive,
+            LifeState::Alive => self.dying(1),
+            LifeState::Dead if self.lookup(neighborhood) => LifeState::Alive,
+            LifeState::Dead => LifeState::Dead,
+            LifeState::Dying(k) => self.dying(k + 1),
+        }
+    }
+
+    /// `k`th refractory state, or dead once the rule runs out of them
+    pub(crate) fn dying(&self, k: u8) -> LifeState {
+        dying(self.states, k)
+    }
+}
+
+/// `k`th refractory state of a rule with `states` states, or dead once it runs out of them
+pub(crate) fn dying(states: u8, k: u8) -> LifeState {
+    if k + 1 < states {
+        LifeState::Dying(k)
+    } else {
+        LifeState::Dead
+    }
+}
+
+/// Error returned when a rulestring can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRuleError(pub(crate) String);
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rulestring: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseRuleError {}
+
+/// Turn one half of a rulestring into the neighborhoods (without the cell itself) it lists. Each neighbor
+/// count can be followed by Hensel letters (`2ak`) or by a `-` and the letters it leaves out (`2-ak`)
+fn parse_counts(counts: &str, rule: &str) -> Result<Vec<usize>, ParseRuleError> {
+    let mut listed = Vec::new();
+    let mut chars = counts.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = match c.to_digit(10) {
+            Some(n) if n <= 8 => n as usize,
+            _ => {
+                return Err(ParseRuleError(format!(
+                    "`{}` isn't a neighbor count (0-8) in `{}`",
+                    c, rule
+                )))
+            }
+        };
+        let without = chars.next_if_eq(&'-').is_some();
+        let mut letters = Vec::new();
+        while let Some(letter) = chars.next_if(char::is_ascii_lowercase) {
+            letters.push(letter);
+        }
+        if without && letters.is_empty() {
+            return Err(ParseRuleError(format!(
+                "`{}-` needs letters after it in `{}`",
+                count, rule
+            )));
+        }
+        let mut picked = Vec::new();
+        for letter in letters {
+            picked.extend(neighborhoods(count, Some(letter)).ok_or_else(|| {
+                ParseRuleError(format!(
+                    "`{}{}` isn't a neighborhood in `{}`",
+                    count, letter, rule
+                ))
+            })?);
+        }
+        let all = neighborhoods(count, None).unwrap();
+        if picked.is_empty() {
+            listed.extend(all);
+        } else if without {
+            listed.extend(all.into_iter().filter(|i| !picked.contains(i)));
+        } else {
+            listed.extend(picked);
+        }
+    }
+    Ok(listed)
+}
+
+/// Turn the counts of one half of a rulestring for any [`Tiling`] but squares into a bitmask
+fn parse_totals(counts: &str, tiling: Tiling, rule: &str) -> Result<u64, ParseRuleError> {
+    let mut mask = 0;
+    for c in counts.chars() {
+        match c.to_digit(13) {
+            Some(n) if n as usize <= tiling.max_neighbors() => mask |= 1 << n,
+            _ => {
+                return Err(ParseRuleError(format!(
+                    "`{}` isn't a neighbor count (0-{}) of {} cells in `{}`",
+                    c,
+                    tiling.max_neighbors(),
+                    tiling,
+                    rule
+                )))
+            }
+        }
+    }
+    Ok(mask)
+}
+
+/// Turn one half of a rulestring written as a comma separated list (`2,3,10`) into a bitmask. That's how
+/// counts (or sums of [`crate::Kernel`] weights) past what digits can hold are written, up to 63
+fn parse_list(counts: &str, rule: &str) -> Result<u64, ParseRuleError> {
+    let mut mask = 0;
+    for n in counts.split(',').filter(|n| !n.is_empty()) {
+        match n.parse::<u8>() {
+            Ok(n) if n < 64 => mask |= 1 << n,
+            _ => {
+                return Err(ParseRuleError(format!(
+                    "`{}` isn't a neighbor count (0-63) in `{}`",
+                    n, rule
+                )))
+            }
+        }
+    }
+    Ok(mask)
+}
+
+/// Write a bitmask of counts as a comma separated list (with a trailing comma when there's only one,
+/// so it doesn't read as digits)
+fn list(mask: u64) -> String {
+    let counts = (0..64)
+        .filter(|n| mask & (1 << n) != 0)
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>();
+    if counts.len() == 1 {
+        format!("{},", counts[0])
+    } else {
+        counts.join(",")
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut parts = s.split('/');
+        let (left, right) = match (parts.next(), parts.next()) {
+            (Some(left), Some(right)) => (left, right),
+            _ => {
+                return Err(ParseRuleError(format!(
+                    "`{}` needs a `/` between its halves",
+                    s
+                )))
+            }
+        };
+        // B2/S345/C4 or 345/2/4
+        let states = match parts.next() {
+            Some(c) => {
+                let c = c
+                    .strip_prefix('C')
+                    .or_else(|| c.strip_prefix('c'))
+                    .unwrap_or(c);
+                match c.parse::<u8>() {
+                    Ok(n) if n >= 2 => n,
+                    _ => {
+                        return Err(ParseRuleError(format!(
+                            "`{}` isn't a state count (2-255) in `{}`",
+                            c, s
+                        )))
+                    }
+                }
+            }
+            None => 2,
+        };
+        if parts.next().is_some() {
+            return Err(ParseRuleError(format!("`{}` has too many `/`", s)));
+        }
+        let prefixed = |part: &str, p: char| {
+            part.strip_prefix(p)
+                .or_else(|| part.strip_prefix(p.to_ascii_lowercase()))
+                .map(str::to_owned)
+        };
+        // B2/S34H: the second half ends with the suffix of the tiling
+        let (right, tiling) = Tiling::ALL[1..]
+            .iter()
+            .find_map(|&tiling| {
+                let suffix = tiling.suffix();
+                right
+                    .strip_suffix(suffix)
+                    .or_else(|| right.strip_suffix(&suffix.to_ascii_lowercase()))
+                    .map(|right| (right, tiling))
+            })
+            .unwrap_or((right, Tiling::Square));
+        let (birth, survival) = match (prefixed(left, 'B'), prefixed(right, 'S')) {
+            // B3/S23
+            (Some(b), Some(s)) => (b, s),
+            _ => match (prefixed(left, 'S'), prefixed(right, 'B')) {
+                // S23/B3
+                (Some(s), Some(b)) => (b, s),
+                // 23/3 (survival first)
+                _ => (right.to_owned(), left.to_owned()),
+            },
+        };
+        if tiling != Tiling::Square {
+            let totals = |half: &str| match half.contains(',') {
+                true => parse_list(half, s),
+                false => parse_totals(half, tiling, s),
+            };
+            return Ok(
+                Self::from_counts(totals(&birth)?, totals(&survival)?, tiling).with_states(states),
+            );
+        }
+        // B2,3,10/S: lists go through the counts, everything else through the lookup table
+        let mut table = [0; 8];
+        let mut lists = (0, 0);
+        for (half, center, list) in [(&birth, 0, &mut lists.0), (&survival, CENTER, &mut lists.1)] {
+            if half.contains(',') {
+                *list = parse_list(half, s)?;
+            } else {
+                for i in parse_counts(half, s)? {
+                    set(&mut table, i | center);
+                }
+            }
+        }
+        let mut rule = Self::from_table(table);
+        let listed = Self::from_masks(lists.0, lists.1);
+        for (t, l) in rule.table.iter_mut().zip(listed.table) {
+            *t |= l;
+        }
+        rule.birth |= listed.birth;
+        rule.survival |= listed.survival;
+        Ok(rule.with_states(states))
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // plain digits for the counts where every arrangement matches, the shortest of the letters
+        // that match and the letters that don't for the others
+        let counts = |center: usize, mask: u64| {
+            if mask >> 9 != 0 {
+                return list(mask);
+            }
+            let mut half = String::new();
+            for n in 0..=8 {
+                let all = neighborhoods(n, None).unwrap();
+                let matching = all.iter().filter(|&i| self.lookup(i | center)).count();
+                if matching == all.len() {
+                    half.push_str(&n.to_string());
+                } else if matching > 0 {
+                    let (with, without): (String, String) = letters(n).partition(|&letter| {
+                        neighborhoods(n, Some(letter))
+                            .unwrap()
+                            .iter()
+                            .all(|i| self.lookup(i | center))
+                    });
+                    half.push_str(&n.to_string());
+                    if with.len() <= without.len() {
+                        half.push_str(&with);
+                    } else {
+                        half.push('-');
+                        half.push_str(&without);
+                    }
+                }
+            }
+            half
+        };
+        if self.tiling != Tiling::Square {
+            let totals = |mask: u64| {
+                if mask >> (self.tiling.max_neighbors() + 1) != 0 {
+                    return list(mask);
+                }
+                (0..=self.tiling.max_neighbors() as u32)
+                    .filter(|n| mask & (1 << n) != 0)
+                    .filter_map(|n| char::from_digit(n, 13))
+                    .collect::<String>()
+            };
+            write!(
+                f,
+                "B{}/S{}{}",
+                totals(self.birth),
+                totals(self.survival),
+                self.tiling.suffix()
+            )?;
+        } else {
+            write!(
+                f,
+                "B{}/S{}",
+                counts(0, self.birth),
+                counts(CENTER, self.survival)
+            )?;
+        }
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+//! Reproducible random soups
+use crate::engine::Engine;
+use crate::game::LifeState;
+use crate::symmetry::Symmetry;
+use std::hash::{BuildHasher, Hasher};
+
+/// Small seeded random number generator (SplitMix64). The same seed always gives the same numbers,
+/// on every platform
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Generator starting from `seed`
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Generator for the cell at column `x` and row `y` in `generation`, so what's rolled for a cell
+    /// doesn't depend on the order cells are looked at. `salt` tells apart what the rolls are for
+    pub(crate) fn for_cell(seed: u64, generation: u64, (x, y): (i32, i32), salt: u64) -> Self {
+        Self::new(
+            seed ^ generation.wrapping_mul(0x9e3779b97f4a7c15)
+                ^ (x as u32 as u64).wrapping_mul(0xc2b2ae3d27d4eb4f)
+                ^ (y as u32 as u64).wrapping_mul(0x165667b19e3779f9)
+                ^ salt.wrapping_mul(0xd6e8feb86659fd93),
+        )
+    }
+
+    /// Next 64 random bits
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random number in `0.0..1.0`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Recipe for a random soup: the same [`Soup`] always fills the same cells
+///
+/// ```
+/// use gol_engine::{Engine, SparseGame, Soup};
+///
+/// let soup = Soup::new(42).with_density(0.3).with_size(Some(16));
+/// let mut a = SparseGame::new();
+/// let mut b = SparseGame::new();
+/// soup.fill(&mut a, 0, 0, 64, 64);
+/// soup.fill(&mut b, 0, 0, 64, 64);
+/// assert_eq!(Engine::cells_hash(&a), Engine::cells_hash(&b));
+/// // only the 16x16 box in the middle is filled
+/// let (x0, y0, x1, y1) = Engine::bounding_box(&a).unwrap();
+/// assert!(x0 >= 24 && y0 >= 24 && x1 < 40 && y1 < 40);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Soup {
+    /// Seed of the [`Rng`] deciding which cells are alive
+    pub seed: u64,
+    /// Chance of each cell being alive (`0.0` to `1.0`)
+    pub density: f64,
+    /// Only fill a `size` x `size` box in the middle of the area (`None` fills all of it)
+    pub size: Option<u32>,
+    /// Symmetry forced onto the soup. [`Symmetry::C4`] and [`Symmetry::D8`] shrink the box to a square
+    pub symmetry: Symmetry,
+    /// How many teams alive cells are split between, alive cells are [`LifeState::Alive`] when it's 1 and
+    /// the [`LifeState`] whose [`LifeState::index`] is their team otherwise (see [`crate::Colours`])
+    pub colours: u8,
+}
+
+impl Soup {
+    /// Half alive, half dead soup filling the whole area
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            density: 0.5,
+            size: None,
+            symmetry: Symmetry::C1,
+            colours: 1,
+        }
+    }
+
+    /// Soup with a seed nobody picked
+    pub fn random() -> Self {
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_u128(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos()),
+        );
+        Self::new(hasher.finish())
+    }
+
+    /// Same soup with each cell alive with chance `density` (clamped to `0.0..=1.0`)
+    pub fn with_density(self, density: f64) -> Self {
+        Self {
+            density: density.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Same soup only filling a `size` x `size` box in the middle of the area (`None` fills all of it)
+    pub fn with_size(self, size: Option<u32>) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Same soup forced into `symmetry`
+    pub fn with_symmetry(self, symmetry: Symmetry) -> Self {
+        Self { symmetry, ..self }
+    }
+
+    /// Same soup with alive cells split between `colours` teams (at least 1)
+    pub fn with_colours(self, colours: u8) -> Self {
+        Self {
+            colours: colours.max(1),
+            ..self
+        }
+    }
+
+    /// Same soup with the seed that comes after this one, for the next press of R
+    pub fn next(self) -> Self {
+        Self {
+            seed: Rng::new(self.seed).next_u64(),
+            ..self
+        }
+    }
+
+    /// Clear `engine` and fill the `columns` x `rows` area starting at (`x`, `y`). The soup never spills
+    /// out of the area, a `size` bigger than it is cut down to fit
+    pub fn fill<E: Engine + ?Sized>(
+        &self,
+        engine: &mut E,
+        x: i64,
+        y: i64,
+        columns: u32,
+        rows: u32,
+    ) {
+        let (area_columns, area_rows) = (columns, rows);
+        let (mut columns, mut rows) = match self.size {
+            Some(size) => (size.min(columns), size.min(rows)),
+            None => (columns, rows),
+        };
+        if self.symmetry.needs_square() {
+            columns = columns.min(rows);
+            rows = columns;
+        }
+        // the soup sits in the middle of the area
+        let x = x + (area_columns as i64 - columns as i64) / 2;
+        let y = y + (area_rows as i64 - rows as i64) / 2;
+        engine.clear();
+        let mut rng = Rng::new(self.seed);
+        // 0 for dead cells, the team of alive ones. Single team soups don't draw teams, so they stay the
+        // same as before colours
+        let picks: Vec<u8> = (0..columns as usize * rows as usize)
+            .map(|_| match rng.next_f64() < self.density {
+                false => 0,
+                true if self.colours > 1 => 1 + (rng.next_u64() % self.colours as u64) as u8,
+                true => 1,
+            })
+            .collect();
+        for cy in 0..rows {
+            for cx in 0..columns {
+                // every image of a cell copies the one that comes first, row by row
+                let (px, py) = self
+                    .symmetry
+                    .images(cx, cy, columns, rows)
+                    .into_iter()
+                    .min_by_key(|&(ix, iy)| (iy, ix))
+                    .unwrap_or((cx, cy));
+                let team = picks[py as usize * columns as usize + px as usize];
+                if team > 0 {
+                    engine.set_cell(x + cx as i64, y + cy as i64, LifeState::from_index(team));
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Soup {
+    /// `seed=... density=... [size=...]`, as saved with recordings
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "seed={} density={}", self.seed, self.density)?;
+        if let Some(size) = self.size {
+            write!(f, " size={}", size)?;
+        }
+        if self.symmetry != Symmetry::C1 {
+            write!(f, " symmetry={}", self.symmetry)?;
+        }
+        if self.colours > 1 {
+            write!(f, " colours={}", self.colours)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+//! Unbounded universe that only remembers the cells that aren't dead
+use crate::engine::Engine;
+use crate::game::LifeState;
+use crate::rule::Rule;
+use crate::stats::StepStats;
+use std::collections::HashMap;
+
+/// Unbounded counterpart of [`crate::Game`]. Cells are addressed with [`i64`] coordinates and
+/// anything that isn't [`LifeState::Dead`] is kept in a [`HashMap`], so patterns can travel as far as they like.
+///
+/// Rules with `B0` would fill the whole universe in one generation, so births on 0 neighbors never happen here.
+/// Neighbors are only counted, so non-totalistic rules only get the counts every arrangement matches, and
+/// cells are always squares (the [`crate::Tiling`] of the rule is ignored)
+///
+/// ```
+/// use gol_engine::{LifeState, SparseGame};
+///
+/// let mut game = SparseGame::new();
+/// for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+///     game.set_cell(x, y, LifeState::Alive);
+/// }
+/// for _ in 0..400 {
+///     game.step();
+/// }
+/// // the glider is long gone from where any window would've been
+/// assert_eq!(game.population(), 5);
+/// assert_eq!(game.bounding_box(), Some((100, 100, 102, 102)));
+/// ```
+#[derive(Clone, Default)]
+pub struct SparseGame {
+    cells: HashMap<(i64, i64), LifeState>,
+    generation: u64,
+    rule: Rule,
+    /// What the last generation changed
+    last_step: StepStats,
+}
+
+impl SparseGame {
+    /// Empty universe running Conway's `B3/S23`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// [`Rule`] used to decide births and survivals
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Replace the [`Rule`] used for the next generations
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// How many generations have been simulated so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Births, deaths and changed cells of the last generation
+    pub fn last_step(&self) -> StepStats {
+        self.last_step
+    }
+
+    /// [`LifeState`] of the cell at (`x`, `y`). Everything never touched is dead
+    pub fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        self.cells.get(&(x, y)).copied().unwrap_or(LifeState::Dead)
+    }
+
+    /// Set the [`LifeState`] of the cell at (`x`, `y`)
+    pub fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        if state == LifeState::Dead {
+            self.cells.remove(&(x, y));
+        } else {
+            self.cells.insert((x, y), state);
+        }
+    }
+
+    /// Flip the cell at (`x`, `y`) between alive and dead (dying cells come back alive)
+    pub fn toggle_cell(&mut self, x: i64, y: i64) {
+        let state = if self.get_cell(x, y) == LifeState::Alive {
+            LifeState::Dead
+        } else {
+            LifeState::Alive
+        };
+        self.set_cell(x, y, state);
+    }
+
+    /// Iterate over every cell that isn't dead (in no particular order)
+    pub fn cells(&self) -> impl Iterator<Item = ((i64, i64), LifeState)> + '_ {
+        self.cells.iter().map(|(pos, state)| (*pos, *state))
+    }
+
+    /// How many cells are currently alive
+    pub fn population(&self) -> usize {
+        self.cells
+            .values()
+            .filter(|s| **s == LifeState::Alive)
+            .count()
+    }
+
+    /// Smallest (`min_x`, `min_y`, `max_x`, `max_y`) box holding every cell that isn't dead (`None` when empty)
+    pub fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        self.cells.keys().fold(None, |bb, &(x, y)| match bb {
+            None => Some((x, y, x, y)),
+            Some((x0, y0, x1, y1)) => Some((x0.min(x), y0.min(y), x1.max(x), y1.max(y))),
+        })
+    }
+
+    /// Kill every cell in the universe
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Advance the universe by one generation
+    pub fn step(&mut self) {
+        self.step_from();
+    }
+
+    /// Advance the universe by one generation and hand back the cells it had before
+    fn step_from(&mut self) -> HashMap<(i64, i64), LifeState> {
+        // only cells next to an alive cell can change, so that's all we look at
+        let mut alive_neighbors: HashMap<(i64, i64), usize> = HashMap::new();
+        for (&(x, y), _) in self.cells.iter().filter(|(_, s)| **s == LifeState::Alive) {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx != 0 || dy != 0 {
+                        *alive_neighbors.entry((x + dx, y + dy)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        let mut next = HashMap::with_capacity(self.cells.len());
+        let mut stats = StepStats::default();
+        for (&pos, &state) in &self.cells {
+            let n = alive_neighbors.get(&pos).copied().unwrap_or(0);
+            let new_state = self.rule.next_state(state, n);
+            stats.record(state, new_state);
+            if new_state != LifeState::Dead {
+                next.insert(pos, new_state);
+            }
+        }
+        for (pos, n) in alive_neighbors {
+            if !self.cells.contains_key(&pos) && self.rule.born(n) {
+                stats.record(LifeState::Dead, LifeState::Alive);
+                next.insert(pos, LifeState::Alive);
+            }
+        }
+        self.last_step = stats;
+        self.generation += 1;
+        std::mem::replace(&mut self.cells, next)
+    }
+}
+
+impl Engine for SparseGame {
+    fn name(&self) -> &'static str {
+        "sparse"
+    }
+
+    fn rule_name(&self) -> String {
+        self.rule.to_string()
+    }
+
+    fn states(&self) -> u8 {
+        self.rule.states()
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        SparseGame::get_cell(self, x, y)
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        SparseGame::set_cell(self, x, y, state);
+    }
+
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            SparseGame::step(self);
+        }
+    }
+
+    fn step_with_changes(&mut self, generations: u64) -> Option<Vec<((i64, i64), LifeState)>> {
+        let before = match generations {
+            1 => self.step_from(),
+            _ => {
+                let before = self.cells.clone();
+                Engine::step(self, generations);
+                before
+            }
+        };
+        let mut changes = before
+            .iter()
+            .filter(|(pos, state)| self.cells.get(pos) != Some(state))
+            .map(|(&pos, &state)| (pos, state))
+            .collect::<Vec<_>>();
+        changes.extend(
+            self.cells
+                .keys()
+                .filter(|pos| !before.contains_key(pos))
+                .map(|&pos| (pos, LifeState::Dead)),
+        );
+        Some(changes)
+    }
+
+    fn can_rewind(&self) -> bool {
+        true
+    }
+
+    fn last_step(&self) -> Option<StepStats> {
+        Some(self.last_step)
+    }
+
+    fn generation(&self) -> u128 {
+        self.generation as u128
+    }
+
+    fn set_generation(&mut self, generation: u128) {
+        self.generation = u64::try_from(generation).unwrap_or(u64::MAX);
+    }
+
+    fn population(&self) -> u64 {
+        SparseGame::population(self) as u64
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (i64, i64)> + '_> {
+        Box::new(
+            self.cells()
+                .filter(|(_, state)| *state == LifeState::Alive)
+                .map(|(pos, _)| pos),
+        )
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), LifeState)> + '_> {
+        Box::new(SparseGame::cells(self))
+    }
+
+    fn clear(&mut self) {
+        SparseGame::clear(self);
+    }
+}
@@ -0,0 +1,149 @@
+//! Per-generation statistics and writing them out as CSV or JSON lines
+use crate::engine::Engine;
+use crate::game::LifeState;
+use std::io::Write;
+
+/// What a single generation changed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StepStats {
+    /// Cells that came alive
+    pub births: u64,
+    /// Cells that stopped being alive (dead or dying)
+    pub deaths: u64,
+    /// Cells whose [`LifeState`] changed at all (dying cells getting older count too)
+    pub changed: u64,
+}
+
+impl StepStats {
+    /// Count a cell going from `old` to `new`
+    pub fn record(&mut self, old: LifeState, new: LifeState) {
+        if old == new {
+            return;
+        }
+        self.changed += 1;
+        if new == LifeState::Alive {
+            self.births += 1;
+        } else if old == LifeState::Alive {
+            self.deaths += 1;
+        }
+    }
+}
+
+/// One row of the time series written with `--stats-out`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GenerationStats {
+    pub generation: u128,
+    pub population: u64,
+    /// `None` when the backend doesn't keep track (see [`Engine::last_step`]), so births, deaths and
+    /// changed cells are written empty (`null` in JSON lines)
+    pub step: Option<StepStats>,
+    /// (`min_x`, `min_y`, `max_x`, `max_y`) of the alive cells
+    pub bounding_box: Option<(i64, i64, i64, i64)>,
+}
+
+impl GenerationStats {
+    /// Statistics of the current generation of `engine`
+    pub fn of<E: Engine + ?Sized>(engine: &E) -> Self {
+        Self {
+            generation: engine.generation(),
+            population: engine.population(),
+            step: engine.last_step(),
+            bounding_box: engine.bounding_box(),
+        }
+    }
+}
+
+/// File format [`StatsWriter`] writes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatsFormat {
+    /// Comma separated values with a header line. Unknown values are left empty
+    Csv,
+    /// One JSON object per line. Unknown values are `null`
+    JsonLines,
+}
+
+impl StatsFormat {
+    /// Pick the format from a file name: `.json` and `.jsonl` are JSON lines, anything else is CSV
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json" | "jsonl" | "ndjson") => StatsFormat::JsonLines,
+            _ => StatsFormat::Csv,
+        }
+    }
+}
+
+const CSV_HEADER: &str = "generation,population,births,deaths,changed,min_x,min_y,max_x,max_y";
+
+/// Writes [`GenerationStats`] one row at a time
+///
+/// ```
+/// use gol_engine::{Engine, GenerationStats, LifeState, SparseGame, StatsFormat, StatsWriter};
+///
+/// let mut game = SparseGame::new();
+/// for x in 0..3 {
+///     game.set_cell(x, 0, LifeState::Alive);
+/// }
+/// let mut writer = StatsWriter::new(Vec::new(), StatsFormat::Csv).unwrap();
+/// Engine::step(&mut game, 1);
+/// writer.write(&GenerationStats::of(&game)).unwrap();
+/// let csv = String::from_utf8(writer.into_inner()).unwrap();
+/// assert_eq!(csv.lines().nth(1), Some("1,3,2,2,4,1,-1,1,1"));
+/// ```
+pub struct StatsWriter<W: Write> {
+    out: W,
+    format: StatsFormat,
+}
+
+impl<W: Write> StatsWriter<W> {
+    /// Start writing to `out` (writes the CSV header right away)
+    pub fn new(mut out: W, format: StatsFormat) -> std::io::Result<Self> {
+        if format == StatsFormat::Csv {
+            writeln!(out, "{}", CSV_HEADER)?;
+        }
+        Ok(Self { out, format })
+    }
+
+    /// Append one generation
+    pub fn write(&mut self, stats: &GenerationStats) -> std::io::Result<()> {
+        let step = stats
+            .step
+            .map(|s| [s.births, s.deaths, s.changed].map(|v| v.to_string()));
+        let bounding_box = stats
+            .bounding_box
+            .map(|(x0, y0, x1, y1)| [x0, y0, x1, y1].map(|v| v.to_string()));
+        match self.format {
+            StatsFormat::Csv => writeln!(
+                self.out,
+                "{},{},{},{}",
+                stats.generation,
+                stats.population,
+                step.map_or(",,".to_string(), |s| s.join(",")),
+                bounding_box.map_or(",,,".to_string(), |b| b.join(",")),
+            ),
+            StatsFormat::JsonLines => {
+                let [births, deaths, changed] =
+                    step.unwrap_or_else(|| ["null", "null", "null"].map(String::from));
+                writeln!(
+                    self.out,
+                    "{{\"generation\":{},\"population\":{},\"births\":{},\"deaths\":{},\"changed\":{},\"bounding_box\":{}}}",
+                    stats.generation,
+                    stats.population,
+                    births,
+                    deaths,
+                    changed,
+                    bounding_box.map_or("null".to_string(), |b| format!("[{}]", b.join(","))),
+                )
+            }
+        }
+    }
+
+    /// Push everything written so far to `out`
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+
+    /// Get `out` back
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
@@ -0,0 +1,154 @@
+//! Probabilistic births, survivals and background noise
+use crate::game::LifeState;
+use crate::rule::Rule;
+use crate::soup::Rng;
+
+/// Chances a [`crate::Game`] rolls for every cell of every generation.
+///
+/// Each roll comes from the seed, the generation and the cell alone, so a run depends on neither the
+/// number of threads nor the order cells are looked at, and going back in history then stepping again
+/// gives the same generations
+///
+/// ```
+/// use gol_engine::{Game, LifeState, Stochastic};
+///
+/// // births never happen, so a blinker fades away
+/// let mut game = Game::new(5, 5, 1);
+/// game.set_stochastic(Some(Stochastic::new(7).with_birth(0.0)));
+/// for x in 1..4 {
+///     game.set_cell(x, 2, LifeState::Alive);
+/// }
+/// game.step();
+/// assert_eq!(game.population(), 1);
+/// game.step();
+/// assert_eq!(game.population(), 0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stochastic {
+    /// Seed of the rolls
+    pub seed: u64,
+    /// Chance of a birth the rule asks for happening (`0.0` to `1.0`), the cell stays as it is otherwise
+    pub birth: f64,
+    /// Chance of a survival the rule asks for happening (`0.0` to `1.0`), the cell dies otherwise
+    pub survival: f64,
+    /// Chance of each cell being flipped after the rule, alive cells die and the others come alive
+    pub noise: f64,
+}
+
+/// What a roll is for, so the rolls of a cell don't depend on each other
+#[derive(Clone, Copy)]
+enum Roll {
+    Birth = 1,
+    Survival,
+    Noise,
+}
+
+impl Stochastic {
+    /// Rolls that always go the rule's way, with no noise
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            birth: 1.0,
+            survival: 1.0,
+            noise: 0.0,
+        }
+    }
+
+    /// Same seed with births happening with chance `birth`, clamped to `0.0..=1.0`
+    pub fn with_birth(self, birth: f64) -> Self {
+        Self {
+            birth: birth.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Same seed with survivals happening with chance `survival`, clamped to `0.0..=1.0`
+    pub fn with_survival(self, survival: f64) -> Self {
+        Self {
+            survival: survival.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Same seed with every cell flipped with chance `noise` after the rule, clamped to `0.0..=1.0`
+    pub fn with_noise(self, noise: f64) -> Self {
+        Self {
+            noise: noise.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Do the rolls always go the rule's way?
+    pub fn is_deterministic(&self) -> bool {
+        self.birth == 1.0 && self.survival == 1.0 && self.noise == 0.0
+    }
+
+    /// Number in `0.0..1.0` rolled for `roll` of the cell at column `x` and row `y` in `generation`
+    fn roll(&self, roll: Roll, generation: u64, x: i32, y: i32) -> f64 {
+        Rng::for_cell(self.seed, generation, (x, y), roll as u64).next_f64()
+    }
+
+    /// State the cell at column `x` and row `y` ends up in when `rule` takes it from `old` to `new` in
+    /// `generation`
+    pub fn next_state(
+        &self,
+        rule: &Rule,
+        generation: u64,
+        (x, y): (i32, i32),
+        old: LifeState,
+        new: LifeState,
+    ) -> LifeState {
+        let state = match (old, new) {
+            (LifeState::Alive, LifeState::Alive)
+                if self.roll(Roll::Survival, generation, x, y) >= self.survival =>
+            {
+                rule.dying(1)
+            }
+            (old, LifeState::Alive)
+                if old != LifeState::Alive
+                    && self.roll(Roll::Birth, generation, x, y) >= self.birth =>
+            {
+                old
+            }
+            _ => new,
+        };
+        self.noisy(rule, generation, (x, y), state)
+    }
+
+    /// State the cell at column `x` and row `y` ends up in when the noise of `generation` is rolled for
+    /// it in `state`. Cells an [`crate::Update::Alpha`] update leaves as they are still go through it, so
+    /// `noise` is the share of every cell flipped
+    pub fn noisy(
+        &self,
+        rule: &Rule,
+        generation: u64,
+        (x, y): (i32, i32),
+        state: LifeState,
+    ) -> LifeState {
+        if self.roll(Roll::Noise, generation, x, y) < self.noise {
+            match state {
+                LifeState::Alive => rule.dying(1),
+                _ => LifeState::Alive,
+            }
+        } else {
+            state
+        }
+    }
+}
+
+impl Default for Stochastic {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl std::fmt::Display for Stochastic {
+    /// `seed=... birth=... survival=... noise=...`, like [`crate::Soup`]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "seed={} birth={} survival={} noise={}",
+            self.seed, self.birth, self.survival, self.noise
+        )
+    }
+}
@@ -0,0 +1,119 @@
+//! Symmetries forced onto random soups, after the ones apgsearch uses
+use std::fmt;
+use std::str::FromStr;
+
+/// Symmetry of a random [`crate::Soup`]. Every cell has the same state as its images under the symmetry
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// No symmetry at all
+    #[default]
+    C1,
+    /// Same after half a turn
+    C2,
+    /// Same after a quarter turn
+    C4,
+    /// Mirrored left to right
+    D2x,
+    /// Mirrored top to bottom
+    D2y,
+    /// Mirrored left to right and top to bottom
+    D4,
+    /// Same after quarter turns and mirrored along both axes and both diagonals
+    D8,
+}
+
+impl Symmetry {
+    /// Every symmetry, for listing them and cycling through them
+    pub const ALL: [Symmetry; 7] = [
+        Symmetry::C1,
+        Symmetry::C2,
+        Symmetry::C4,
+        Symmetry::D2x,
+        Symmetry::D2y,
+        Symmetry::D4,
+        Symmetry::D8,
+    ];
+
+    /// The symmetry after this one in [`Symmetry::ALL`] (wrapping back to [`Symmetry::C1`])
+    pub fn next(self) -> Self {
+        let i = Symmetry::ALL.iter().position(|s| *s == self).unwrap_or(0);
+        Symmetry::ALL[(i + 1) % Symmetry::ALL.len()]
+    }
+
+    /// Quarter turns and diagonal mirrors only work on squares
+    pub fn needs_square(&self) -> bool {
+        matches!(self, Symmetry::C4 | Symmetry::D8)
+    }
+
+    /// Cells (column `x`, row `y` included) that have to match (`x`, `y`) in a `columns` x `rows` box.
+    /// The box has to be square if [`Symmetry::needs_square`]
+    pub fn images(&self, x: u32, y: u32, columns: u32, rows: u32) -> Vec<(u32, u32)> {
+        let (mx, my) = (columns - 1 - x, rows - 1 - y);
+        match self {
+            Symmetry::C1 => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), (mx, my)],
+            Symmetry::C4 => vec![(x, y), (my, x), (mx, my), (y, mx)],
+            Symmetry::D2x => vec![(x, y), (mx, y)],
+            Symmetry::D2y => vec![(x, y), (x, my)],
+            Symmetry::D4 => vec![(x, y), (mx, y), (x, my), (mx, my)],
+            Symmetry::D8 => vec![
+                (x, y),
+                (my, x),
+                (mx, my),
+                (y, mx),
+                (mx, y),
+                (x, my),
+                (y, x),
+                (my, mx),
+            ],
+        }
+    }
+}
+
+/// Error returned when a symmetry name isn't known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSymmetryError(String);
+
+impl fmt::Display for ParseSymmetryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown symmetry `{}` (expected one of: {})",
+            self.0,
+            Symmetry::ALL.map(|s| s.to_string()).join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ParseSymmetryError {}
+
+impl FromStr for Symmetry {
+    type Err = ParseSymmetryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "c1" | "none" => Ok(Symmetry::C1),
+            "c2" => Ok(Symmetry::C2),
+            "c4" => Ok(Symmetry::C4),
+            "d2x" | "d2" => Ok(Symmetry::D2x),
+            "d2y" => Ok(Symmetry::D2y),
+            "d4" => Ok(Symmetry::D4),
+            "d8" => Ok(Symmetry::D8),
+            _ => Err(ParseSymmetryError(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Symmetry::C1 => "C1",
+            Symmetry::C2 => "C2",
+            Symmetry::C4 => "C4",
+            Symmetry::D2x => "D2x",
+            Symmetry::D2y => "D2y",
+            Symmetry::D4 => "D4",
+            Symmetry::D8 => "D8",
+        })
+    }
+}
@@ -0,0 +1,746 @@
+//! Rule tables: any amount of states, with the transitions listed one by one like Golly's `.rule` files
+use crate::engine::{grid_changes, grid_coordinates, Engine};
+use crate::game::LifeState;
+use crate::ltl::Neighborhood;
+use crate::topology::Topology;
+use std::collections::{HashMap, HashSet};
+use std::{fmt, str::FromStr};
+
+/// Set of states a transition accepts at one position, a bit per state
+type States = [u64; 4];
+
+fn single(state: u8) -> States {
+    let mut set = [0; 4];
+    set[state as usize / 64] |= 1 << (state % 64);
+    set
+}
+
+fn members(set: &States) -> impl Iterator<Item = u8> + '_ {
+    (0..=255u8).filter(move |&s| contains(set, s))
+}
+
+fn contains(set: &States, state: u8) -> bool {
+    set[state as usize / 64] & (1 << (state % 64)) != 0
+}
+
+/// Can every state of `neighbors` be given a set of `slots` (sorted) holding it, each set used once? That's
+/// how `permute` transitions match, whatever order the neighbors come in
+fn fills(slots: &[States], used: &mut [bool], neighbors: &[u8]) -> bool {
+    let Some((&state, rest)) = neighbors.split_first() else {
+        return true;
+    };
+    for i in 0..slots.len() {
+        // of identical sets, only the first free one is worth trying
+        if used[i]
+            || !contains(&slots[i], state)
+            || (i > 0 && slots[i - 1] == slots[i] && !used[i - 1])
+        {
+            continue;
+        }
+        used[i] = true;
+        if fills(slots, used, rest) {
+            used[i] = false;
+            return true;
+        }
+        used[i] = false;
+    }
+    false
+}
+
+/// Golly's `@TABLE` section in the `.rule` file format with Wireworld built in.
+///
+/// Each transition lists the state of the cell, then the states of its neighbors clockwise from the
+/// one above it (N, NE, E, SE, S, SW, W, NW, or N, E, S, W for `vonNeumann`), then the state the cell
+/// goes to. Variables stand for a set of states, and one used more than once has to be the same state
+/// everywhere. The first transition matching a cell wins, and cells nothing matches keep their state:
+/// ```
+/// use gol_engine::RuleTable;
+///
+/// let table: RuleTable = "@RULE Spread
+/// @TABLE
+/// n_states:3
+/// neighborhood:vonNeumann
+/// symmetries:rotate4
+/// var a={0,1,2}
+/// var b={0,1,2}
+/// var c={0,1,2}
+/// 0,1,a,b,c,1
+/// 1,a,b,c,a,2
+/// @COLORS
+/// 1 255 0 0".parse().unwrap();
+/// assert_eq!(table.name(), "Spread");
+/// assert_eq!(table.next_state(&[0, 0, 0, 1, 0]), 1);
+/// assert_eq!(table.next_state(&[1, 2, 0, 0, 2]), 2);
+/// assert_eq!(table.next_state(&[1, 2, 0, 1, 0]), 1);
+/// assert_eq!(table.color(1), Some((255, 0, 0)));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleTable {
+    name: String,
+    states: u8,
+    neighborhood: Neighborhood,
+    /// State every transition leads to, once variables and symmetries are expanded
+    outputs: Vec<u8>,
+    /// For each position of the neighborhood and each state, bits of the transitions accepting it there
+    accepts: Vec<u64>,
+    /// Sorted neighbor sets of every `permute` transition (empty for the others). Their neighbors accept
+    /// any state in `accepts` and are matched against these instead, in any order
+    permuted: Vec<Vec<States>>,
+    /// `@COLORS` of every state
+    colors: Vec<Option<(u8, u8, u8)>>,
+}
+
+/// Wireworld: empty, electron head, electron tail and conductor
+const WIREWORLD: &str = "@RULE WireWorld
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+var a={0,1,2,3}
+var b={0,1,2,3}
+var c={0,1,2,3}
+var d={0,1,2,3}
+var e={0,1,2,3}
+var f={0,1,2,3}
+var g={0,1,2,3}
+var h={0,1,2,3}
+var o={0,2,3}
+var p={0,2,3}
+var q={0,2,3}
+var r={0,2,3}
+var s={0,2,3}
+var t={0,2,3}
+var u={0,2,3}
+# heads become tails, tails become conductors again
+1,a,b,c,d,e,f,g,h,2
+2,a,b,c,d,e,f,g,h,3
+# conductors with 1 or 2 heads around become heads
+3,1,o,p,q,r,s,t,u,1
+3,1,1,o,p,q,r,s,t,1
+@COLORS
+0 48 48 48
+1 0 128 255
+2 255 255 255
+3 255 128 0
+";
+
+/// Symmetries a transition stands for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Symmetries {
+    None,
+    Rotate4,
+    Rotate8,
+    ReflectHorizontal,
+    Rotate4Reflect,
+    Rotate8Reflect,
+    Permute,
+}
+
+impl Symmetries {
+    /// Every arrangement of `neighbors` (clockwise from N) the symmetries turn them into, without repeats
+    fn variants(self, neighbors: &[States]) -> Vec<Vec<States>> {
+        let n = neighbors.len();
+        let rotations = |neighbors: &[States], step: usize| {
+            (0..n)
+                .step_by(step)
+                .map(|k| {
+                    let mut rotated = neighbors.to_vec();
+                    rotated.rotate_right(k);
+                    rotated
+                })
+                .collect::<Vec<_>>()
+        };
+        // N and S stay, the rest swap sides
+        let reflected = std::iter::once(neighbors[0])
+            .chain(neighbors[1..].iter().rev().copied())
+            .collect::<Vec<_>>();
+        let all = match self {
+            Symmetries::None => vec![neighbors.to_vec()],
+            Symmetries::Rotate4 => rotations(neighbors, n / 4),
+            Symmetries::Rotate8 => rotations(neighbors, 1),
+            Symmetries::ReflectHorizontal => vec![neighbors.to_vec(), reflected],
+            Symmetries::Rotate4Reflect => {
+                let mut all = rotations(neighbors, n / 4);
+                all.extend(rotations(&reflected, n / 4));
+                all
+            }
+            Symmetries::Rotate8Reflect => {
+                let mut all = rotations(neighbors, 1);
+                all.extend(rotations(&reflected, 1));
+                all
+            }
+            // matched by counting the neighbors instead (see `fills`)
+            Symmetries::Permute => vec![neighbors.to_vec()],
+        };
+        let mut seen = HashSet::new();
+        all.into_iter().filter(|v| seen.insert(v.clone())).collect()
+    }
+}
+
+impl RuleTable {
+    /// Wireworld, where electrons (a head followed by a tail) run along conductors. A conductor becomes
+    /// a head when 1 or 2 of its neighbors are heads
+    pub fn wireworld() -> Self {
+        WIREWORLD.parse().unwrap()
+    }
+
+    /// Name given by `@RULE`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Total amount of states a cell can be in (`n_states`)
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Neighbors the transitions list, [`Neighborhood::Moore`] or [`Neighborhood::VonNeumann`]
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// How many transitions there are once variables and symmetries are expanded (`permute` ones aren't,
+    /// they match any order of their neighbors as they are)
+    pub fn transitions(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// (red, green, blue) the `@COLORS` section gives `state` (`None` if it doesn't)
+    pub fn color(&self, state: u8) -> Option<(u8, u8, u8)> {
+        self.colors.get(state as usize).copied().flatten()
+    }
+
+    /// State a cell goes to. `cells` holds its own state then the ones of its neighbors, clockwise from N
+    pub fn next_state(&self, cells: &[u8]) -> u8 {
+        let states = self.states as usize;
+        let words = self.outputs.len().div_ceil(64);
+        for word in 0..words {
+            let mut matching = u64::MAX;
+            for (position, &state) in cells.iter().enumerate() {
+                matching &= self.accepts[(position * states + state as usize) * words + word];
+                if matching == 0 {
+                    break;
+                }
+            }
+            while matching != 0 {
+                let t = word * 64 + matching.trailing_zeros() as usize;
+                let slots = &self.permuted[t];
+                if slots.is_empty() || fills(slots, &mut [false; 8][..slots.len()], &cells[1..]) {
+                    return self.outputs[t];
+                }
+                matching &= matching - 1;
+            }
+        }
+        cells[0]
+    }
+
+    /// (`dx`, `dy`) of the neighbors, in the order transitions list them
+    fn offsets(&self) -> &'static [(i32, i32)] {
+        match self.neighborhood {
+            Neighborhood::Moore => &[
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+            ],
+            Neighborhood::VonNeumann => &[(0, -1), (1, 0), (0, 1), (-1, 0)],
+        }
+    }
+}
+
+/// Error returned when a rule table can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTableError(String);
+
+impl fmt::Display for ParseTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rule table: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseTableError {}
+
+impl FromStr for RuleTable {
+    type Err = ParseTableError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name = String::new();
+        let mut section = String::new();
+        let mut states: Option<u8> = None;
+        let mut neighborhood: Option<Neighborhood> = None;
+        let mut symmetries = Symmetries::None;
+        let mut variables: HashMap<&str, States> = HashMap::new();
+        // inputs, new state and whether the neighbors come in any order
+        let mut transitions: Vec<(Vec<States>, u8, bool)> = Vec::new();
+        let mut colors = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let error = |message: String| ParseTableError(format!("line {}: {}", i + 1, message));
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('@') {
+                let (title, rest) = header
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((header, ""));
+                section = title.to_ascii_uppercase();
+                if section == "RULE" {
+                    name = rest.trim().to_string();
+                }
+                continue;
+            }
+            match section.as_str() {
+                "TABLE" => {}
+                "COLORS" => {
+                    let numbers = line
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|n| !n.is_empty())
+                        .map(|n| {
+                            n.parse::<u8>()
+                                .map_err(|_| error(format!("`{}` isn't a state or a colour", n)))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    match numbers[..] {
+                        [state, r, g, b] => colors.push((state, state, (r, g, b), (r, g, b))),
+                        // a gradient from state 1 to the last one
+                        [r0, g0, b0, r1, g1, b1] => {
+                            colors.push((1, u8::MAX, (r0, g0, b0), (r1, g1, b1)))
+                        }
+                        _ => {
+                            return Err(error(
+                                "colours are `state red green blue` or a gradient of 6 numbers"
+                                    .to_string(),
+                            ))
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim();
+                match key.trim() {
+                    "n_states" => match value.parse::<u32>() {
+                        Ok(n @ 2..=255) => states = Some(n as u8),
+                        _ => {
+                            return Err(error(format!(
+                                "n_states has to be 2 to 255, not {}",
+                                value
+                            )))
+                        }
+                    },
+                    "neighborhood" => {
+                        neighborhood = Some(match value.to_ascii_lowercase().as_str() {
+                            "moore" => Neighborhood::Moore,
+                            "vonneumann" => Neighborhood::VonNeumann,
+                            _ => {
+                                return Err(error(format!(
+                                    "`{}` neighborhoods aren't supported (Moore or vonNeumann)",
+                                    value
+                                )))
+                            }
+                        })
+                    }
+                    "symmetries" => {
+                        symmetries = match value {
+                            "none" => Symmetries::None,
+                            "rotate4" => Symmetries::Rotate4,
+                            "rotate8" => Symmetries::Rotate8,
+                            "reflect_horizontal" => Symmetries::ReflectHorizontal,
+                            "rotate4reflect" => Symmetries::Rotate4Reflect,
+                            "rotate8reflect" => Symmetries::Rotate8Reflect,
+                            "permute" => Symmetries::Permute,
+                            _ => return Err(error(format!("unknown symmetries `{}`", value))),
+                        }
+                    }
+                    key => return Err(error(format!("unknown setting `{}`", key))),
+                }
+                continue;
+            }
+            let (Some(states), Some(neighborhood)) = (states, neighborhood) else {
+                return Err(error(
+                    "n_states and neighborhood have to come before variables and transitions"
+                        .to_string(),
+                ));
+            };
+            if neighborhood == Neighborhood::VonNeumann
+                && matches!(symmetries, Symmetries::Rotate8 | Symmetries::Rotate8Reflect)
+            {
+                return Err(error(
+                    "vonNeumann neighborhoods can only be turned a quarter at a time".to_string(),
+                ));
+            }
+            let state = |token: &str, variables: &HashMap<&str, States>| match token.parse::<u8>() {
+                Ok(state) if state < states => Ok(single(state)),
+                Ok(state) => Err(error(format!(
+                    "state {} is past n_states ({})",
+                    state, states
+                ))),
+                Err(_) => variables
+                    .get(token)
+                    .copied()
+                    .ok_or_else(|| error(format!("unknown variable `{}`", token))),
+            };
+            if let Some(var) = line.strip_prefix("var ") {
+                let (var_name, set) = var
+                    .split_once('=')
+                    .ok_or_else(|| error("variables are written `var a={0,1,2}`".to_string()))?;
+                let set = set
+                    .trim()
+                    .strip_prefix('{')
+                    .and_then(|set| set.strip_suffix('}'))
+                    .ok_or_else(|| error("the states of a variable go between `{}`".to_string()))?;
+                let mut value = [0; 4];
+                for token in set.split(',') {
+                    for (word, bits) in value.iter_mut().zip(state(token.trim(), &variables)?) {
+                        *word |= bits;
+                    }
+                }
+                variables.insert(var_name.trim(), value);
+                continue;
+            }
+            // `0,1,2`, `0 1 2`, or `012` when every state is a single digit
+            let tokens: Vec<&str> = if line.contains(',') {
+                line.split(',').map(str::trim).collect()
+            } else if line.contains(char::is_whitespace) {
+                line.split_whitespace().collect()
+            } else {
+                line.char_indices()
+                    .map(|(i, c)| &line[i..i + c.len_utf8()])
+                    .collect()
+            };
+            let positions = match neighborhood {
+                Neighborhood::Moore => 9,
+                Neighborhood::VonNeumann => 5,
+            };
+            if tokens.len() != positions + 1 {
+                return Err(error(format!(
+                    "transitions need {} states (the cell, its neighbors and the new state), not {}",
+                    positions + 1,
+                    tokens.len()
+                )));
+            }
+            let sets = tokens
+                .iter()
+                .map(|token| state(token, &variables))
+                .collect::<Result<Vec<_>, _>>()?;
+            // variables showing up more than once are bound: every one of them is the same state
+            let mut bound: Vec<&str> = Vec::new();
+            for (j, token) in tokens.iter().enumerate() {
+                if variables.contains_key(token)
+                    && tokens[j + 1..].contains(token)
+                    && !bound.contains(token)
+                {
+                    bound.push(token);
+                }
+            }
+            let output = tokens[positions];
+            if variables.contains_key(output) && !bound.contains(&output) {
+                return Err(error(format!(
+                    "the new state `{}` is a variable none of the neighbors use",
+                    output
+                )));
+            }
+            let mut assignments: Vec<Vec<u8>> = vec![Vec::new()];
+            for var in &bound {
+                assignments = assignments
+                    .into_iter()
+                    .flat_map(|assignment| {
+                        members(&variables[var]).map(move |s| {
+                            let mut assignment = assignment.clone();
+                            assignment.push(s);
+                            assignment
+                        })
+                    })
+                    .collect();
+            }
+            for assignment in assignments {
+                let value = |j: usize| match bound.iter().position(|var| *var == tokens[j]) {
+                    Some(k) => single(assignment[k]),
+                    None => sets[j],
+                };
+                let inputs = (0..positions).map(value).collect::<Vec<_>>();
+                let output = members(&value(positions)).next().unwrap();
+                let permuted = symmetries == Symmetries::Permute;
+                for neighbors in symmetries.variants(&inputs[1..]) {
+                    let mut inputs = vec![inputs[0]];
+                    inputs.extend(neighbors);
+                    if permuted {
+                        inputs[1..].sort_unstable();
+                    }
+                    transitions.push((inputs, output, permuted));
+                }
+            }
+        }
+        let (Some(states), Some(neighborhood)) = (states, neighborhood) else {
+            return Err(ParseTableError(
+                "it needs an @TABLE section with n_states and neighborhood".to_string(),
+            ));
+        };
+        let words = transitions.len().div_ceil(64);
+        let positions = match neighborhood {
+            Neighborhood::Moore => 9,
+            Neighborhood::VonNeumann => 5,
+        };
+        let mut accepts = vec![0; positions * states as usize * words];
+        for (t, (inputs, _, permuted)) in transitions.iter().enumerate() {
+            for (position, set) in inputs.iter().enumerate() {
+                let set = if *permuted && position > 0 {
+                    &[u64::MAX; 4]
+                } else {
+                    set
+                };
+                for state in members(set).take_while(|&state| state < states) {
+                    accepts[(position * states as usize + state as usize) * words + t / 64] |=
+                        1 << (t % 64);
+                }
+            }
+        }
+        let mut palette = vec![None; states as usize];
+        for (first, last, (r0, g0, b0), (r1, g1, b1)) in colors {
+            let last = last.min(states - 1);
+            for state in first..=last {
+                let t = if last > first {
+                    (state - first) as f32 / (last - first) as f32
+                } else {
+                    0.0
+                };
+                let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+                if let Some(color) = palette.get_mut(state as usize) {
+                    *color = Some((mix(r0, r1), mix(g0, g1), mix(b0, b1)));
+                }
+            }
+        }
+        Ok(Self {
+            name,
+            states,
+            neighborhood,
+            outputs: transitions.iter().map(|(_, output, _)| *output).collect(),
+            accepts,
+            permuted: transitions
+                .into_iter()
+                .map(|(inputs, _, permuted)| match permuted {
+                    true => inputs[1..].to_vec(),
+                    false => Vec::new(),
+                })
+                .collect(),
+            colors: palette,
+        })
+    }
+}
+
+/// Bounded grid running a [`RuleTable`].
+///
+/// Cells hold any state of the table. Through [`Engine`], state `n` is the [`LifeState`] whose
+/// [`LifeState::index`] is `n`, and every state but 0 counts as alive
+pub struct TableGame {
+    columns: u32,
+    rows: u32,
+    /// State of every cell, row by row
+    cells: Vec<u8>,
+    generation: u64,
+    rule: RuleTable,
+    topology: Topology,
+}
+
+impl TableGame {
+    /// Build a `columns` x `rows` grid of cells in state 0 running `rule`
+    pub fn new(columns: u32, rows: u32, rule: RuleTable) -> Self {
+        Self {
+            columns,
+            rows,
+            cells: vec![0; columns as usize * rows as usize],
+            generation: 0,
+            rule,
+            topology: Topology::default(),
+        }
+    }
+
+    /// How many cells there are on each row
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// How many rows there are in the grid
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// [`RuleTable`] deciding what every cell becomes
+    pub fn rule(&self) -> &RuleTable {
+        &self.rule
+    }
+
+    /// Replace the [`RuleTable`] used for the next generations. Cells in states it doesn't have go back to 0
+    pub fn set_rule(&mut self, rule: RuleTable) {
+        for cell in &mut self.cells {
+            if *cell >= rule.states {
+                *cell = 0;
+            }
+        }
+        self.rule = rule;
+    }
+
+    /// [`Topology`] deciding what's on the other side of the grid edges
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Change how the grid edges are glued together
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// How many generations have been simulated so far
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if (0..self.columns as i32).contains(&x) && (0..self.rows as i32).contains(&y) {
+            Some(y as usize * self.columns as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// State of the cell at column `x` and row `y` (`None` if it's outside of the grid)
+    pub fn get_cell(&self, x: i32, y: i32) -> Option<u8> {
+        self.index(x, y).map(|i| self.cells[i])
+    }
+
+    /// Set the state of the cell at column `x` and row `y`. Cells outside of the grid and states the
+    /// table doesn't have are ignored
+    pub fn set_cell(&mut self, x: i32, y: i32, state: u8) {
+        if let Some(i) = self.index(x, y).filter(|_| state < self.rule.states) {
+            self.cells[i] = state;
+        }
+    }
+
+    /// How many cells aren't in state 0
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&c| c != 0).count()
+    }
+
+    /// Put every cell back to state 0
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+    }
+
+    /// Advance the grid by one generation
+    pub fn step(&mut self) {
+        let (columns, rows) = (self.columns as i32, self.rows as i32);
+        let offsets = self.rule.offsets();
+        let mut around = vec![0; offsets.len() + 1];
+        let next = (0..rows)
+            .flat_map(|y| (0..columns).map(move |x| (x, y)))
+            .zip(&self.cells)
+            .map(|((x, y), &state)| {
+                around[0] = state;
+                for (cell, (dx, dy)) in around[1..].iter_mut().zip(offsets) {
+                    *cell = self
+                        .topology
+                        .wrap(x + dx, y + dy, columns, rows)
+                        .and_then(|(x, y)| self.index(x, y))
+                        .map_or(0, |i| self.cells[i]);
+                }
+                self.rule.next_state(&around)
+            })
+            .collect();
+        self.cells = next;
+        self.generation += 1;
+    }
+}
+
+impl Engine for TableGame {
+    fn name(&self) -> &'static str {
+        "table"
+    }
+
+    fn rule_name(&self) -> String {
+        self.rule.name.clone()
+    }
+
+    fn states(&self) -> u8 {
+        self.rule.states
+    }
+
+    fn topology(&self) -> Option<Topology> {
+        Some(self.topology)
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> LifeState {
+        LifeState::from_index(
+            grid_coordinates(x, y)
+                .and_then(|(x, y)| TableGame::get_cell(self, x, y))
+                .unwrap_or(0),
+        )
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: LifeState) {
+        if let Some((x, y)) = grid_coordinates(x, y) {
+            TableGame::set_cell(self, x, y, state.index());
+        }
+    }
+
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            TableGame::step(self);
+        }
+    }
+
+    fn step_with_changes(&mut self, generations: u64) -> Option<Vec<((i64, i64), LifeState)>> {
+        let before = self.cells.clone();
+        Engine::step(self, generations);
+        Some(grid_changes(
+            &before,
+            &self.cells,
+            self.columns,
+            LifeState::from_index,
+        ))
+    }
+
+    fn can_rewind(&self) -> bool {
+        true
+    }
+
+    fn generation(&self) -> u128 {
+        self.generation as u128
+    }
+
+    fn set_generation(&mut self, generation: u128) {
+        self.generation = u64::try_from(generation).unwrap_or(u64::MAX);
+    }
+
+    fn population(&self) -> u64 {
+        TableGame::population(self) as u64
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (i64, i64)> + '_> {
+        Box::new(self.cells().map(|(pos, _)| pos))
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), LifeState)> + '_> {
+        let columns = self.columns as usize;
+        Box::new(
+            self.cells
+                .iter()
+                .enumerate()
+                .filter(|(_, &c)| c != 0)
+                .map(move |(i, &c)| {
+                    (
+                        ((i % columns) as i64, (i / columns) as i64),
+                        LifeState::from_index(c),
+                    )
+                }),
+        )
+    }
+
+    fn clear(&mut self) {
+        TableGame::clear(self);
+    }
+}
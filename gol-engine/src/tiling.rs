@@ -0,0 +1,142 @@
+//! Shapes cells can have: squares, hexagons and triangles
+use std::fmt;
+
+/// How the plane is cut into cells. Every tiling still addresses cells with (`x`, `y`) pairs:
+///
+/// - hexagons use the skewed square grid Golly uses: the 6 neighbors are the Moore ones but the
+///   top right and bottom left corners. Each row is drawn half a cell left of the row above it
+/// - triangles alternate between pointing up (when `x + y` is even) and down along each row. Every
+///   triangle is 1 cell wide but only half a cell away from the next one, and has 12 neighbors (the
+///   3 sharing an edge with it and the 9 only touching one of its corners)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Tiling {
+    /// Square cells with the 8 Moore neighbors
+    #[default]
+    Square,
+    /// Hexagonal cells with 6 neighbors (`H` rules)
+    Hexagonal,
+    /// Triangular cells with 12 neighbors (`L` rules)
+    Triangular,
+}
+
+const MOORE: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+const HEXAGONAL: [(i32, i32); 6] = [(-1, -1), (0, -1), (-1, 0), (1, 0), (0, 1), (1, 1)];
+
+/// Neighbors of a triangle pointing up: 3 cells above its tip, 4 on its row and 5 below its base
+const TRIANGLE_UP: [(i32, i32); 12] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-2, 0),
+    (-1, 0),
+    (1, 0),
+    (2, 0),
+    (-2, 1),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (2, 1),
+];
+
+/// [`TRIANGLE_UP`] upside down
+const TRIANGLE_DOWN: [(i32, i32); 12] = [
+    (-2, -1),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (2, -1),
+    (-2, 0),
+    (-1, 0),
+    (1, 0),
+    (2, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+impl Tiling {
+    /// All of the tilings
+    pub const ALL: [Tiling; 3] = [Tiling::Square, Tiling::Hexagonal, Tiling::Triangular];
+
+    /// Offsets from the cell at (`x`, `y`) to each of its neighbors
+    pub fn neighbors(&self, x: i32, y: i32) -> &'static [(i32, i32)] {
+        match self {
+            Tiling::Square => &MOORE,
+            Tiling::Hexagonal => &HEXAGONAL,
+            Tiling::Triangular if (x + y).rem_euclid(2) == 0 => &TRIANGLE_UP,
+            Tiling::Triangular => &TRIANGLE_DOWN,
+        }
+    }
+
+    /// How many neighbors every cell has
+    pub fn max_neighbors(&self) -> usize {
+        self.neighbors(0, 0).len()
+    }
+
+    /// Letter rulestrings of this tiling end with (nothing for squares)
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Tiling::Square => "",
+            Tiling::Hexagonal => "H",
+            Tiling::Triangular => "L",
+        }
+    }
+
+    /// Cell under the point (`x`, `y`) of a drawing where every row is 1 unit high, squares and hexagons are
+    /// 1 unit wide and triangles are 1 unit wide but half a unit apart. Cell (0, 0) starts at the origin
+    pub fn cell_at(&self, x: f64, y: f64) -> (i64, i64) {
+        let row = y.floor();
+        match self {
+            Tiling::Square => (x.floor() as i64, row as i64),
+            Tiling::Hexagonal => {
+                // closest center among the rows around, which is what makes the cells hexagons
+                let center = |column: f64, row: f64| (column + 0.5 - row / 2.0, row + 0.5);
+                (-1..=1)
+                    .map(|dy| {
+                        let row = row + dy as f64;
+                        let column = (x - 0.5 + row / 2.0).round();
+                        let (cx, cy) = center(column, row);
+                        (
+                            (cx - x).powi(2) + (cy - y).powi(2),
+                            (column as i64, row as i64),
+                        )
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+                    .unwrap()
+                    .1
+            }
+            Tiling::Triangular => {
+                // the triangles `k - 1` and `k` share this half unit wide strip, split by one of their edges
+                let k = (2.0 * x).floor();
+                let (u, v) = (2.0 * x - k, y - row);
+                let up = (k as i64 + row as i64).rem_euclid(2) == 0;
+                let in_k = if up { v >= 1.0 - u } else { v <= u };
+                (if in_k { k as i64 } else { k as i64 - 1 }, row as i64)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Tiling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Tiling::Square => "square",
+                Tiling::Hexagonal => "hexagonal",
+                Tiling::Triangular => "triangular",
+            }
+        )
+    }
+}
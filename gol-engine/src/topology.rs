@@ -0,0 +1,118 @@
+//! How the edges of a finite grid are glued together
+use std::{fmt, str::FromStr};
+
+/// Shape of the finite universe a [`crate::Game`] lives in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// Plain bounded grid. Everything outside of it is always dead
+    #[default]
+    Plane,
+    /// Left edge is glued to the right edge and top edge to the bottom edge
+    Torus,
+    /// Like [`Topology::Torus`] but the top and bottom edges are glued with a twist (mirrored on x)
+    KleinBottle,
+    /// Both pairs of opposite edges are glued with a twist (a.k.a. the real projective plane)
+    CrossSurface,
+    /// Top edge is glued to the left edge and bottom edge to the right edge. Only works on square grids
+    Sphere,
+    /// Every edge is a mirror. Cells past an edge are the reflection of the cells right before it
+    Reflective,
+}
+
+impl Topology {
+    /// All of the topologies, in the order they're listed on the CLI
+    pub const ALL: [Topology; 6] = [
+        Topology::Plane,
+        Topology::Torus,
+        Topology::KleinBottle,
+        Topology::CrossSurface,
+        Topology::Sphere,
+        Topology::Reflective,
+    ];
+
+    /// Map the cell at column `x` and row `y` onto a `columns` x `rows` grid.
+    /// Returns `None` if the cell falls off the grid.
+    ///
+    /// Cells past a corner of a [`Topology::CrossSurface`] or [`Topology::Sphere`] are always dead,
+    /// since fewer than 4 cells meet at those corners
+    pub fn wrap(&self, x: i32, y: i32, columns: i32, rows: i32) -> Option<(i32, i32)> {
+        let x_inside = (0..columns).contains(&x);
+        let y_inside = (0..rows).contains(&y);
+        let mirror = |v: i32, size: i32| {
+            if v < 0 {
+                -v - 1
+            } else if v >= size {
+                2 * size - 1 - v
+            } else {
+                v
+            }
+        };
+        let (x, y) = match self {
+            _ if x_inside && y_inside => return Some((x, y)),
+            Topology::Plane => return None,
+            Topology::Torus => (x.rem_euclid(columns), y.rem_euclid(rows)),
+            Topology::KleinBottle if y_inside => (x.rem_euclid(columns), y),
+            Topology::KleinBottle => (columns - 1 - x.rem_euclid(columns), y.rem_euclid(rows)),
+            Topology::CrossSurface | Topology::Sphere if !x_inside && !y_inside => return None,
+            Topology::CrossSurface if !x_inside => (x.rem_euclid(columns), rows - 1 - y),
+            Topology::CrossSurface => (columns - 1 - x, y.rem_euclid(rows)),
+            // the seams are quarter turns around the top left and bottom right corners
+            Topology::Sphere if x < 0 => (y, -x - 1),
+            Topology::Sphere if x >= columns => (y, 2 * columns - 1 - x),
+            Topology::Sphere if y < 0 => (-y - 1, x),
+            Topology::Sphere => (2 * rows - 1 - y, x),
+            Topology::Reflective => (mirror(x, columns), mirror(y, rows)),
+        };
+        if (0..columns).contains(&x) && (0..rows).contains(&y) {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+}
+
+/// Error returned when a topology name isn't known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTopologyError(String);
+
+impl fmt::Display for ParseTopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown topology `{}` (expected one of: {})",
+            self.0,
+            Topology::ALL.map(|t| t.to_string()).join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ParseTopologyError {}
+
+impl FromStr for Topology {
+    type Err = ParseTopologyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "plane" => Ok(Topology::Plane),
+            "torus" => Ok(Topology::Torus),
+            "klein-bottle" | "klein" => Ok(Topology::KleinBottle),
+            "cross-surface" => Ok(Topology::CrossSurface),
+            "sphere" => Ok(Topology::Sphere),
+            "reflective" | "mirror" => Ok(Topology::Reflective),
+            _ => Err(ParseTopologyError(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Topology::Plane => "plane",
+            Topology::Torus => "torus",
+            Topology::KleinBottle => "klein-bottle",
+            Topology::CrossSurface => "cross-surface",
+            Topology::Sphere => "sphere",
+            Topology::Reflective => "reflective",
+        })
+    }
+}
@@ -0,0 +1,79 @@
+//! Asynchronous ways of updating the cells of a [`crate::Game`]
+use std::{fmt, str::FromStr};
+
+/// Order the cells of a [`crate::Game`] are updated in during a generation
+///
+/// ```
+/// use gol_engine::Update;
+///
+/// assert_eq!("alpha:0.5".parse::<Update>(), Ok(Update::Alpha(0.5)));
+/// assert_eq!(Update::RandomOrder.to_string(), "random-order");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Update {
+    /// Every cell is updated at once from the previous generation
+    #[default]
+    Synchronous,
+    /// As many times as there are cells, a random cell (maybe one that was already picked) is updated in
+    /// place, seeing the cells updated before it
+    RandomSequential,
+    /// Every cell is updated in place once, in a random order
+    RandomOrder,
+    /// Every cell is updated at once, but only takes its new state with this chance (`0.0` to `1.0`). The
+    /// noise of [`crate::Stochastic`] still reaches the cells left as they are
+    Alpha(f64),
+}
+
+impl Update {
+    /// Do cells see the ones updated before them during the same generation?
+    pub fn is_sequential(&self) -> bool {
+        matches!(self, Update::RandomSequential | Update::RandomOrder)
+    }
+}
+
+/// Error returned when an update scheme can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUpdateError(String);
+
+impl fmt::Display for ParseUpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown update scheme `{}` (expected one of: synchronous, random-sequential, random-order, \
+             alpha:<chance from 0.0 to 1.0>)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseUpdateError {}
+
+impl FromStr for Update {
+    type Err = ParseUpdateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        match name.as_str() {
+            "synchronous" | "sync" => Ok(Update::Synchronous),
+            "random-sequential" => Ok(Update::RandomSequential),
+            "random-order" => Ok(Update::RandomOrder),
+            _ => name
+                .strip_prefix("alpha:")
+                .and_then(|alpha| alpha.parse::<f64>().ok())
+                .filter(|alpha| (0.0..=1.0).contains(alpha))
+                .map(Update::Alpha)
+                .ok_or_else(|| ParseUpdateError(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Update {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Update::Synchronous => f.write_str("synchronous"),
+            Update::RandomSequential => f.write_str("random-sequential"),
+            Update::RandomOrder => f.write_str("random-order"),
+            Update::Alpha(alpha) => write!(f, "alpha:{}", alpha),
+        }
+    }
+}
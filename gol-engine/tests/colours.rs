@@ -0,0 +1,102 @@
+use gol_engine::{Colours, DenseGame, Engine, Game, LifeState, Soup};
+use std::collections::BTreeSet;
+
+fn live_cells(engine: &impl Engine) -> BTreeSet<(i64, i64)> {
+    engine.live_cells().collect()
+}
+
+#[test]
+fn names_round_trip() {
+    for colours in Colours::ALL {
+        assert_eq!(colours.to_string().parse::<Colours>(), Ok(colours));
+    }
+    assert_eq!("QuadLife".parse::<Colours>(), Ok(Colours::QuadLife));
+    assert_eq!("none".parse::<Colours>(), Ok(Colours::Single));
+    assert!("triple".parse::<Colours>().is_err());
+}
+
+#[test]
+fn newborns_join_the_majority() {
+    assert_eq!(Colours::Single.newborn(&[1, 1, 1]), 1);
+    assert_eq!(Colours::Immigration.newborn(&[1, 1, 2]), 1);
+    assert_eq!(Colours::Immigration.newborn(&[2, 1, 2]), 2);
+    assert_eq!(Colours::QuadLife.newborn(&[4, 4, 2]), 4);
+    // every team missing but one
+    assert_eq!(Colours::QuadLife.newborn(&[2, 3, 4]), 1);
+    assert_eq!(Colours::QuadLife.newborn(&[1, 2, 3]), 4);
+}
+
+#[test]
+fn births_take_the_colour_of_their_parents() {
+    let mut game = Game::new(10, 10, 1);
+    game.set_colours(Colours::QuadLife);
+    // vertical blinker of 3 teams, the horizontal phase is born from all of them
+    Engine::set_cell(&mut game, 4, 3, LifeState::from_index(1));
+    Engine::set_cell(&mut game, 4, 4, LifeState::from_index(2));
+    Engine::set_cell(&mut game, 4, 5, LifeState::from_index(3));
+    assert_eq!(game.states(), 5);
+    Engine::step(&mut game, 1);
+    assert_eq!(game.get_colour(3, 4), Some(4));
+    assert_eq!(game.get_colour(4, 4), Some(2));
+    assert_eq!(game.get_colour(5, 4), Some(4));
+    assert_eq!(Engine::get_cell(&game, 3, 4), LifeState::from_index(4));
+
+    let mut game = Game::new(10, 10, 1);
+    game.set_colours(Colours::Immigration);
+    Engine::set_cell(&mut game, 4, 3, LifeState::from_index(2));
+    Engine::set_cell(&mut game, 4, 4, LifeState::from_index(1));
+    Engine::set_cell(&mut game, 4, 5, LifeState::from_index(2));
+    Engine::step(&mut game, 1);
+    assert_eq!(game.get_colour(3, 4), Some(2));
+    assert_eq!(game.get_colour(4, 4), Some(1));
+    assert_eq!(game.get_colour(5, 4), Some(2));
+
+    // teams the colours don't have are clamped
+    Engine::set_cell(&mut game, 0, 0, LifeState::from_index(9));
+    assert_eq!(game.get_colour(0, 0), Some(2));
+    game.set_colours(Colours::Single);
+    assert_eq!(Engine::get_cell(&game, 3, 4), LifeState::Alive);
+    assert_eq!(game.get_colour(3, 4), Some(1));
+}
+
+#[test]
+fn colours_dont_change_which_cells_live() {
+    let soup = Soup::new(77).with_density(0.4).with_colours(4);
+    let mut coloured = Game::new(40, 40, 1);
+    coloured.set_colours(Colours::QuadLife);
+    let mut plain = DenseGame::new(40, 40);
+    soup.fill(&mut coloured, 0, 0, 40, 40);
+    for (x, y) in coloured.live_cells() {
+        plain.set_cell(x as i32, y as i32, LifeState::Alive);
+    }
+    for _ in 0..20 {
+        assert_eq!(live_cells(&coloured), live_cells(&plain));
+        Engine::step(&mut coloured, 1);
+        plain.step();
+    }
+}
+
+#[test]
+fn soups_pick_colours() {
+    let mut game = Game::new(30, 30, 1);
+    game.set_colours(Colours::QuadLife);
+    let soup = Soup::new(5).with_colours(4);
+    soup.fill(&mut game, 0, 0, 30, 30);
+    let teams = (0..30)
+        .flat_map(|y| (0..30).map(move |x| (x, y)))
+        .filter_map(|(x, y)| game.get_colour(x, y))
+        .collect::<BTreeSet<_>>();
+    assert_eq!(teams, BTreeSet::from([1, 2, 3, 4]));
+    assert!(soup.to_string().ends_with(" colours=4"));
+
+    // a single team soup fills the same cells as before
+    let mut plain = DenseGame::new(30, 30);
+    let mut single = DenseGame::new(30, 30);
+    Soup::new(5).fill(&mut plain, 0, 0, 30, 30);
+    Soup::new(5).with_colours(1).fill(&mut single, 0, 0, 30, 30);
+    assert_eq!(live_cells(&plain), live_cells(&single));
+    assert_eq!(
+        Soup::new(5).to_string(),
+        Soup::new(5).with_colours(1).to_string()
+    );
+}
@@ -0,0 +1,26 @@
+//! Helpers shared by the integration tests, each test file only uses some of them
+#![allow(dead_code)]
+
+use gol_engine::{Engine, Game, LifeState, Soup};
+use std::collections::BTreeMap;
+
+/// Every cell that isn't dead, with its state
+pub fn snapshot(engine: &impl Engine) -> BTreeMap<(i64, i64), LifeState> {
+    engine.cells().collect()
+}
+
+/// `columns` by `rows` [`Game`] set up by `setup`, then filled with a soup of `density` from `seed`
+pub fn soup_game(
+    columns: u32,
+    rows: u32,
+    seed: u64,
+    density: f64,
+    setup: impl FnOnce(&mut Game),
+) -> Game {
+    let mut game = Game::new(columns, rows, 1);
+    setup(&mut game);
+    Soup::new(seed)
+        .with_density(density)
+        .fill(&mut game, 0, 0, columns, rows);
+    game
+}
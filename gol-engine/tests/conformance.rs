@@ -0,0 +1,189 @@
+//! Known patterns every [`Engine`] has to get right. Adding a backend is one `conformance!` line
+use gol_engine::{
+    DenseGame, Engine, Game, HashLifeGame, LifeState, LtlGame, SparseGame, TableGame,
+};
+use std::collections::BTreeSet;
+
+const BLINKER: [(i64, i64); 3] = [(0, 1), (1, 1), (2, 1)];
+const GLIDER: [(i64, i64); 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+const R_PENTOMINO: [(i64, i64); 5] = [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)];
+
+fn place(engine: &mut impl Engine, cells: &[(i64, i64)], (ox, oy): (i64, i64)) {
+    for &(x, y) in cells {
+        engine.set_cell(ox + x, oy + y, LifeState::Alive);
+    }
+}
+
+fn live_cells(engine: &impl Engine) -> BTreeSet<(i64, i64)> {
+    engine.live_cells().collect()
+}
+
+fn shifted(cells: &[(i64, i64)], (dx, dy): (i64, i64)) -> BTreeSet<(i64, i64)> {
+    cells.iter().map(|(x, y)| (x + dx, y + dy)).collect()
+}
+
+fn blinker_has_period_2(mut engine: impl Engine) {
+    place(&mut engine, &BLINKER, (30, 30));
+    engine.step(1);
+    assert_eq!(
+        live_cells(&engine),
+        shifted(&[(1, 0), (1, 1), (1, 2)], (30, 30))
+    );
+    engine.step(1);
+    assert_eq!(live_cells(&engine), shifted(&BLINKER, (30, 30)));
+    engine.step(9);
+    assert_eq!(engine.population(), 3);
+    assert_eq!(engine.generation(), 11);
+    assert_eq!(engine.bounding_box(), Some((31, 30, 31, 32)));
+}
+
+fn glider_moves_diagonally(mut engine: impl Engine) {
+    place(&mut engine, &GLIDER, (20, 20));
+    engine.step(4);
+    assert_eq!(live_cells(&engine), shifted(&GLIDER, (21, 21)));
+    engine.step(36);
+    assert_eq!(live_cells(&engine), shifted(&GLIDER, (30, 30)));
+    assert_eq!(engine.population(), 5);
+    assert_eq!(engine.bounding_box(), Some((30, 30, 32, 32)));
+}
+
+fn cells_can_be_edited(mut engine: impl Engine) {
+    assert_eq!(engine.population(), 0);
+    assert_eq!(engine.bounding_box(), None);
+    engine.set_cell(5, 6, LifeState::Alive);
+    engine.toggle_cell(7, 8);
+    assert_eq!(engine.get_cell(5, 6), LifeState::Alive);
+    assert_eq!(engine.get_cell(7, 8), LifeState::Alive);
+    assert_eq!(engine.get_cell(6, 7), LifeState::Dead);
+    assert_eq!(engine.bounding_box(), Some((5, 6, 7, 8)));
+    engine.toggle_cell(5, 6);
+    assert_eq!(live_cells(&engine), BTreeSet::from([(7, 8)]));
+    engine.clear();
+    assert_eq!(engine.population(), 0);
+}
+
+fn generation_counter_never_wraps(mut engine: impl Engine) {
+    engine.set_generation(1103);
+    assert_eq!(engine.generation(), 1103);
+    // backends counting in u64 stop at its largest value instead of wrapping around
+    engine.set_generation(u128::MAX);
+    assert!(
+        [u64::MAX as u128, u128::MAX].contains(&engine.generation()),
+        "{}",
+        engine.generation()
+    );
+}
+
+/// Needs a grid of at least 600x600 for the escaping gliders
+fn r_pentomino_stabilises_at_1103<E: Engine>(mut engine: E) -> E {
+    place(&mut engine, &R_PENTOMINO, (300, 300));
+    engine.step(1103);
+    assert_eq!(engine.generation(), 1103);
+    assert_eq!(engine.population(), 116);
+    engine
+}
+
+/// [`r_pentomino_stabilises_at_1103`], then checks that big steps land exactly where single ones do
+fn r_pentomino_stabilises_at_1103_one_step_at_a_time<E: Engine>(new: impl Fn() -> E) {
+    let at_once = r_pentomino_stabilises_at_1103(new());
+    let mut one_by_one = new();
+    place(&mut one_by_one, &R_PENTOMINO, (300, 300));
+    for _ in 0..1103 {
+        one_by_one.step(1);
+    }
+    assert_eq!(live_cells(&one_by_one), live_cells(&at_once));
+    assert_eq!(one_by_one.bounding_box(), at_once.bounding_box());
+}
+
+macro_rules! conformance {
+    ($backend:ident, $new:expr) => {
+        mod $backend {
+            use super::*;
+
+            #[test]
+            fn blinker_has_period_2() {
+                super::blinker_has_period_2($new(64));
+            }
+
+            #[test]
+            fn glider_moves_diagonally() {
+                super::glider_moves_diagonally($new(64));
+            }
+
+            #[test]
+            fn cells_can_be_edited() {
+                super::cells_can_be_edited($new(64));
+            }
+
+            #[test]
+            fn generation_counter_never_wraps() {
+                super::generation_counter_never_wraps($new(64));
+            }
+        }
+    };
+}
+
+conformance!(game, |size| Game::new(size, size, 1));
+conformance!(dense, |size| DenseGame::new(size, size));
+conformance!(sparse, |_| SparseGame::new());
+conformance!(hashlife, |_| HashLifeGame::new());
+// range 1 Moore without the middle cell, counts 2..3 to survive and 3 to be born, is Conway's Life
+conformance!(ltl, |size| LtlGame::new(
+    size,
+    size,
+    "R1,C0,M0,S2..3,B3..3,NM".parse().unwrap()
+));
+conformance!(table, |size| TableGame::new(
+    size,
+    size,
+    LIFE_TABLE.parse().unwrap()
+));
+
+/// Conway's Life as a rule table: births with 3 neighbors, survivals with 2 or 3, everything else dies
+const LIFE_TABLE: &str = "@RULE Life
+@TABLE
+n_states:2
+neighborhood:Moore
+symmetries:permute
+var a={0,1}
+var b={0,1}
+var c={0,1}
+var d={0,1}
+var e={0,1}
+var f={0,1}
+var g={0,1}
+var h={0,1}
+0,1,1,1,0,0,0,0,0,1
+1,1,1,0,0,0,0,0,0,1
+1,1,1,1,0,0,0,0,0,1
+1,a,b,c,d,e,f,g,h,0
+";
+
+// Game::step(n) is a plain loop of single steps, so it's only run once, on every core. Each generation goes
+// through the 360000 cells of the HashMap, so that's still minutes with optimisations (about 15 on a single
+// core) and hours without: debug builds skip it, run `cargo test --release` to check it
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    ignore = "too slow without optimisations, run with --release"
+)]
+fn game_r_pentomino_stabilises_at_1103() {
+    let mut game = Game::new(600, 600, 1);
+    game.set_threads(std::thread::available_parallelism().map_or(1, |n| n.get()));
+    r_pentomino_stabilises_at_1103(game);
+}
+
+#[test]
+fn dense_r_pentomino_stabilises_at_1103() {
+    r_pentomino_stabilises_at_1103_one_step_at_a_time(|| DenseGame::new(600, 600));
+}
+
+#[test]
+fn sparse_r_pentomino_stabilises_at_1103() {
+    r_pentomino_stabilises_at_1103_one_step_at_a_time(SparseGame::new);
+}
+
+#[test]
+fn hashlife_r_pentomino_stabilises_at_1103() {
+    r_pentomino_stabilises_at_1103_one_step_at_a_time(HashLifeGame::new);
+}
@@ -0,0 +1,136 @@
+use gol_engine::{
+    Cycle, CycleDetector, DenseGame, Engine, Game, LifeState, Soup, SparseGame, Topology,
+};
+
+fn run_until_cycle(
+    engine: &mut impl Engine,
+    detector: &mut CycleDetector,
+    limit: u64,
+) -> Option<Cycle> {
+    for _ in 0..limit {
+        if let Some(cycle) = detector.observe(engine) {
+            return Some(cycle);
+        }
+        engine.step(1);
+    }
+    None
+}
+
+#[test]
+fn still_life_settles_after_its_predecessors() {
+    // a pre-block turns into a block after one generation
+    let mut game = SparseGame::new();
+    for (x, y) in [(0, 0), (1, 0), (0, 1)] {
+        game.set_cell(x, y, LifeState::Alive);
+    }
+    let cycle = run_until_cycle(&mut game, &mut CycleDetector::new(16), 10);
+    assert_eq!(
+        cycle,
+        Some(Cycle {
+            start: 1,
+            period: 1
+        })
+    );
+}
+
+#[test]
+fn dead_universe_is_stable() {
+    let mut game = DenseGame::new(16, 16);
+    for (x, y) in [(3, 3), (9, 9)] {
+        game.set_cell(x, y, LifeState::Alive);
+    }
+    let cycle = run_until_cycle(&mut game, &mut CycleDetector::new(16), 10);
+    assert_eq!(
+        cycle,
+        Some(Cycle {
+            start: 1,
+            period: 1
+        })
+    );
+    assert_eq!(Engine::population(&game), 0);
+}
+
+#[test]
+fn glider_on_a_torus_comes_back_around() {
+    let mut game = DenseGame::new(8, 8);
+    game.set_topology(Topology::Torus);
+    for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+        game.set_cell(x, y, LifeState::Alive);
+    }
+    let cycle = run_until_cycle(&mut game, &mut CycleDetector::new(64), 100);
+    assert_eq!(
+        cycle,
+        Some(Cycle {
+            start: 0,
+            period: 32
+        })
+    );
+
+    // periods longer than the window go unnoticed
+    let mut game = DenseGame::new(8, 8);
+    game.set_topology(Topology::Torus);
+    for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+        game.set_cell(x, y, LifeState::Alive);
+    }
+    assert_eq!(
+        run_until_cycle(&mut game, &mut CycleDetector::new(16), 100),
+        None
+    );
+}
+
+#[test]
+fn dying_states_are_part_of_the_state() {
+    let mut alive_only = Game::new(10, 10, 1);
+    alive_only.set_rule("B2/S/C3".parse().unwrap());
+    alive_only.set_cell(5, 5, LifeState::Alive);
+    let mut with_dying = Game::new(10, 10, 1);
+    with_dying.set_rule("B2/S/C3".parse().unwrap());
+    with_dying.set_cell(5, 5, LifeState::Alive);
+    with_dying.set_cell(6, 6, LifeState::Dying(1));
+    assert_ne!(alive_only.cells_hash(), with_dying.cells_hash());
+
+    with_dying.set_cell(6, 6, LifeState::Dead);
+    assert_eq!(alive_only.cells_hash(), with_dying.cells_hash());
+}
+
+#[test]
+fn changed_cells_keep_the_same_hash() {
+    let mut game = Game::new(40, 40, 1);
+    game.set_rule("B2/S345/C4".parse().unwrap());
+    game.set_topology(Topology::Torus);
+    Soup::new(3).with_density(0.4).fill(&mut game, 0, 0, 40, 40);
+    let mut full = CycleDetector::new(64);
+    let mut changes = CycleDetector::new(64);
+    assert!(changes.is_empty());
+    for _ in 0..50 {
+        let changed = game.step_with_changes(1).unwrap();
+        // the first call has nothing to build on and hashes every cell
+        assert_eq!(
+            full.observe(&game),
+            changes.observe_changes(&game, &changed)
+        );
+    }
+    assert!(!changes.is_empty());
+
+    // a pre-block settles the same way seen through the cells it changes
+    let mut game = SparseGame::new();
+    for (x, y) in [(0, 0), (1, 0), (0, 1)] {
+        game.set_cell(x, y, LifeState::Alive);
+    }
+    let mut detector = CycleDetector::new(16);
+    detector.observe(&game);
+    let mut cycle = None;
+    for _ in 0..10 {
+        let changed = game.step_with_changes(1).unwrap();
+        cycle = cycle.or(detector.observe_changes(&game, &changed));
+    }
+    assert_eq!(
+        cycle,
+        Some(Cycle {
+            start: 1,
+            period: 1
+        })
+    );
+    detector.clear();
+    assert!(detector.is_empty() && detector.cycle().is_none());
+}
@@ -0,0 +1,148 @@
+use gol_engine::{DenseGame, Engine, Game, LifeState, Rule, Topology};
+
+/// Tiny LCG so the soups are the same on every run
+fn soup(columns: u32, rows: u32, seed: u64) -> Vec<(i32, i32)> {
+    let mut state = seed;
+    let mut cells = Vec::new();
+    for y in 0..rows as i32 {
+        for x in 0..columns as i32 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            if state >> 63 == 1 {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+/// Run the same soup on [`DenseGame`] and the reference [`Game`] and compare every generation
+fn assert_matches_reference(columns: u32, rows: u32, rule: &str, topology: Topology) {
+    let rule: Rule = rule.parse().unwrap();
+    let mut reference = Game::new(columns, rows, 1);
+    let mut dense = DenseGame::new(columns, rows);
+    reference.set_rule(rule);
+    reference.set_topology(topology);
+    dense.set_rule(rule);
+    dense.set_topology(topology);
+    for (x, y) in soup(columns, rows, columns as u64 * 31 + rows as u64) {
+        reference.set_cell(x, y, LifeState::Alive);
+        dense.set_cell(x, y, LifeState::Alive);
+    }
+    for generation in 0..30 {
+        for y in 0..rows as i32 {
+            for x in 0..columns as i32 {
+                assert_eq!(
+                    dense.get_cell(x, y),
+                    reference.get_cell(x, y),
+                    "{} on a {} at ({}, {}) in generation {}",
+                    rule,
+                    topology,
+                    x,
+                    y,
+                    generation
+                );
+            }
+        }
+        assert_eq!(dense.population(), reference.population());
+        reference.step();
+        dense.step();
+    }
+}
+
+#[test]
+fn matches_reference_on_every_topology() {
+    for topology in Topology::ALL {
+        assert_matches_reference(70, 23, "B3/S23", topology);
+        assert_matches_reference(40, 40, "B3/S23", topology);
+    }
+}
+
+#[test]
+fn matches_reference_across_word_boundaries() {
+    for columns in [1, 62, 63, 64, 65, 127, 128, 130] {
+        assert_matches_reference(columns, 9, "B3/S23", Topology::Torus);
+        assert_matches_reference(columns, 9, "B3/S23", Topology::Plane);
+    }
+}
+
+#[test]
+fn matches_reference_on_other_rules() {
+    for rule in [
+        "B36/S23",
+        "B3678/S34678",
+        "B2/S",
+        "B0/S8",
+        "B1357/S1357",
+        "B012345678/S012345678",
+    ] {
+        assert_matches_reference(67, 20, rule, Topology::Torus);
+        assert_matches_reference(67, 20, rule, Topology::Plane);
+    }
+}
+
+#[test]
+fn dying_states_are_dropped() {
+    let mut dense = DenseGame::new(10, 10);
+    dense.set_rule("B2/S345/C4".parse::<Rule>().unwrap());
+    assert_eq!(Engine::states(&dense), 2);
+    dense.set_cell(4, 4, LifeState::Alive);
+    dense.step();
+    // the lone cell would be dying on the game engine
+    assert_eq!(Engine::get_cell(&dense, 4, 4), LifeState::Dead);
+}
+
+#[test]
+fn alive_cells_lists_every_alive_cell() {
+    let cells = soup(100, 10, 7);
+    let mut dense = DenseGame::new(100, 10);
+    for &(x, y) in &cells {
+        dense.set_cell(x, y, LifeState::Alive);
+    }
+    assert_eq!(dense.alive_cells().collect::<Vec<_>>(), cells);
+    assert_eq!(dense.population(), cells.len());
+}
+
+#[test]
+fn threaded_steps_are_identical() {
+    let (columns, rows) = (150, 37);
+    let cells = soup(columns, rows, 42);
+    let mut single = DenseGame::new(columns, rows);
+    for &(x, y) in &cells {
+        single.set_cell(x, y, LifeState::Alive);
+    }
+    for _ in 0..20 {
+        single.step();
+    }
+    let expected = single.alive_cells().collect::<Vec<_>>();
+    for threads in [2, 3, 8, 37, 64] {
+        let mut dense = DenseGame::new(columns, rows);
+        let mut reference = Game::new(columns, rows, 1);
+        dense.set_threads(threads);
+        reference.set_threads(threads);
+        for &(x, y) in &cells {
+            dense.set_cell(x, y, LifeState::Alive);
+            reference.set_cell(x, y, LifeState::Alive);
+        }
+        for _ in 0..20 {
+            dense.step();
+            reference.step();
+        }
+        assert_eq!(
+            dense.alive_cells().collect::<Vec<_>>(),
+            expected,
+            "{} threads",
+            threads
+        );
+        assert_eq!(
+            reference.population(),
+            expected.len(),
+            "{} threads",
+            threads
+        );
+        for &(x, y) in &expected {
+            assert_eq!(reference.get_cell(x, y), Some(LifeState::Alive));
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use gol_engine::{ElementaryGame, ElementaryRule, Engine, History, LifeState, Soup, Topology};
+
+fn row(game: &ElementaryGame, y: i32) -> Vec<bool> {
+    (0..game.columns() as i32)
+        .map(|x| game.get_cell(x, y).unwrap())
+        .collect()
+}
+
+#[test]
+fn rules_round_trip() {
+    for number in [0, 30, 90, 110, 255] {
+        let rule = ElementaryRule::new(number);
+        assert_eq!(rule.to_string().parse::<ElementaryRule>(), Ok(rule));
+    }
+    assert_eq!("w30".parse::<ElementaryRule>().unwrap().number(), 30);
+    for bad in ["W256", "W", "110", "Wx", "B3/S23"] {
+        assert!(bad.parse::<ElementaryRule>().is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn rule_90_draws_pascals_triangle_mod_2() {
+    let (columns, rows) = (41, 20);
+    let mut game = ElementaryGame::new(columns, rows, "W90".parse().unwrap());
+    game.set_cell(20, 0, true);
+    Engine::step(&mut game, 19);
+    for y in 0..rows as i64 {
+        for x in 0..columns as i64 {
+            // row y has a spot every 2 cells from 20 - y, and spot j is C(y, j) mod 2 (odd when the bits
+            // of j are all in y)
+            let k = x - 20 + y;
+            let expected = k >= 0 && k % 2 == 0 && k / 2 <= y && (y & (k / 2)) == k / 2;
+            assert_eq!(
+                Engine::get_cell(&game, x, y) == LifeState::Alive,
+                expected,
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+}
+
+#[test]
+fn rows_follow_the_rule() {
+    let (columns, rows) = (30, 12);
+    for number in [30, 110, 184] {
+        let rule = ElementaryRule::new(number);
+        let mut game = ElementaryGame::new(columns, rows, rule);
+        game.set_topology(Topology::Torus);
+        Soup::new(number as u64).fill(&mut game, 0, 0, columns, rows);
+        // only the current row takes the soup
+        assert!(row(&game, 1).iter().all(|&c| !c));
+        for generation in 0..30 {
+            let last = row(&game, game.current_row() as i32);
+            let alive = |x: i32| last[x.rem_euclid(columns as i32) as usize];
+            let expected = (0..columns as i32)
+                .map(|x| rule.next_state(alive(x - 1), alive(x), alive(x + 1)))
+                .collect::<Vec<_>>();
+            game.step();
+            assert_eq!(
+                row(&game, game.current_row() as i32),
+                expected,
+                "W{} generation {}",
+                number,
+                generation
+            );
+        }
+    }
+}
+
+#[test]
+fn spheres_are_rings() {
+    let mut ring = ElementaryGame::new(16, 8, ElementaryRule::new(30));
+    ring.set_topology(Topology::Torus);
+    let mut sphere = ElementaryGame::new(16, 8, ElementaryRule::new(30));
+    sphere.set_topology(Topology::Sphere);
+    assert_eq!(Engine::topology(&sphere), Some(Topology::Torus));
+    // a cell at the right end reaches around to the left one
+    ring.set_cell(15, 0, true);
+    sphere.set_cell(15, 0, true);
+    sphere.step();
+    assert!(sphere.get_cell(0, 1).unwrap());
+    ring.step();
+    for _ in 0..20 {
+        ring.step();
+        sphere.step();
+        assert_eq!(
+            Engine::live_cells(&ring).collect::<Vec<_>>(),
+            Engine::live_cells(&sphere).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn full_diagrams_scroll() {
+    let mut game = ElementaryGame::new(9, 4, "W110".parse().unwrap());
+    game.set_cell(8, 0, true);
+    Engine::step(&mut game, 3);
+    assert_eq!(game.current_row(), 3);
+    let before = (0..4).map(|y| row(&game, y)).collect::<Vec<_>>();
+    Engine::step(&mut game, 1);
+    assert_eq!(game.current_row(), 3);
+    for y in 0..3 {
+        assert_eq!(row(&game, y), before[y as usize + 1]);
+    }
+    // the future can't be drawn on, and clearing starts over from the top
+    Engine::clear(&mut game);
+    assert_eq!(game.current_row(), 0);
+    game.set_cell(4, 2, true);
+    assert_eq!(game.population(), 0);
+}
+
+#[test]
+fn history_rewinds_until_the_diagram_scrolls() {
+    let mut game = ElementaryGame::new(16, 5, "W30".parse().unwrap());
+    game.set_cell(8, 0, true);
+    let mut history = History::new(1 << 20);
+    let mut diagrams = Vec::new();
+    for _ in 0..4 {
+        diagrams.push((0..5).map(|y| row(&game, y)).collect::<Vec<_>>());
+        history.step(&mut game, 1);
+    }
+    // only the new row is remembered
+    assert!(history.size() < 16 * 5 * std::mem::size_of::<((i64, i64), LifeState)>());
+    while let Some(diagram) = diagrams.pop() {
+        assert!(history.rewind(&mut game));
+        assert_eq!((0..5).map(|y| row(&game, y)).collect::<Vec<_>>(), diagram);
+    }
+    assert_eq!(game.generation(), 0);
+
+    // scrolling moves every row, which isn't remembered
+    for _ in 0..5 {
+        history.step(&mut game, 1);
+    }
+    assert!(!history.rewind(&mut game));
+}
@@ -0,0 +1,170 @@
+use gol_engine::{DenseGame, Engine, Game, HashLifeGame, LifeState, Rule, Soup};
+use std::collections::BTreeSet;
+
+/// Letters every neighbor count can be written with
+const LETTERS: [&str; 9] = [
+    "",
+    "ce",
+    "ceaikn",
+    "ceaiknjqry",
+    "ceaiknjqrtwyz",
+    "ceaiknjqry",
+    "ceaikn",
+    "ce",
+    "",
+];
+
+fn rule(s: &str) -> Rule {
+    s.parse().unwrap()
+}
+
+fn live_cells(game: &dyn Engine) -> BTreeSet<(i64, i64)> {
+    game.live_cells().collect()
+}
+
+/// Rotate or mirror a 3x3 neighborhood index
+fn transform(neighborhood: usize, to: fn(usize, usize) -> (usize, usize)) -> usize {
+    (0..9)
+        .filter(|b| neighborhood & (1 << b) != 0)
+        .map(|b| {
+            let (x, y) = to(b % 3, b / 3);
+            1 << (y * 3 + x)
+        })
+        .sum()
+}
+
+#[test]
+fn hensel_rules_round_trip() {
+    for s in [
+        "B2-a/S12",
+        "B3/S2-i34q",
+        "B2e3ai/S1c23-k",
+        "B3/S23/C3",
+        "B36/S23",
+    ] {
+        assert_eq!(rule(s).to_string(), s);
+    }
+    // the shortest way of writing each count wins
+    assert_eq!(rule("B2ceikn/S").to_string(), "B2-a/S");
+    assert_eq!(rule("B2ceaikn3/S23").to_string(), "B23/S23");
+    assert_eq!(rule("B2-ceaikn3/S23"), rule("B3/S23"));
+}
+
+#[test]
+fn totalistic_rules_stay_totalistic() {
+    assert!(rule("B3/S23").is_totalistic());
+    assert!(rule("B4ceaiknjqrtwyz/S").is_totalistic());
+    assert!(!rule("B2-a/S12").is_totalistic());
+    assert!(!rule("B3/S23-a").is_totalistic());
+}
+
+#[test]
+fn bad_letters_are_rejected() {
+    for s in ["B1a/S", "B3t/S", "B2-/S", "B0c/S", "B9/S", "B2A/S"] {
+        assert!(s.parse::<Rule>().is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn letters_split_every_count_into_symmetric_classes() {
+    let rotate: fn(usize, usize) -> (usize, usize) = |x, y| (2 - y, x);
+    let mirror: fn(usize, usize) -> (usize, usize) = |x, y| (2 - x, y);
+    for (count, letters) in LETTERS.iter().enumerate() {
+        let mut covered = BTreeSet::new();
+        for letter in letters.chars() {
+            let rule = rule(&format!("B{}{}/S", count, letter));
+            let born: Vec<usize> = (0..512).filter(|&i| rule.lookup(i)).collect();
+            for &i in &born {
+                assert_eq!(i & 16, 0);
+                assert_eq!(i.count_ones() as usize, count, "{}{}", count, letter);
+                assert!(rule.lookup(transform(i, rotate)), "{}{}", count, letter);
+                assert!(rule.lookup(transform(i, mirror)), "{}{}", count, letter);
+                assert!(covered.insert(i), "{}{} overlaps", count, letter);
+            }
+        }
+        if !letters.is_empty() {
+            let all = (0..512usize)
+                .filter(|i| i & 16 == 0 && i.count_ones() as usize == count)
+                .count();
+            assert_eq!(
+                covered.len(),
+                all,
+                "letters of {} miss neighborhoods",
+                count
+            );
+        }
+    }
+}
+
+#[test]
+fn letters_above_four_are_complements() {
+    for letter in LETTERS[3].chars() {
+        let three = rule(&format!("B3{}/S", letter));
+        let five = rule(&format!("B5{}/S", letter));
+        for i in (0..512).filter(|i| i & 16 == 0) {
+            assert_eq!(three.lookup(i), five.lookup(i ^ 0b111_101_111));
+        }
+    }
+}
+
+#[test]
+fn block_dies_without_3a_survival() {
+    // every cell of a block has its 3 neighbors in the `a` arrangement
+    let mut game = HashLifeGame::new();
+    game.set_rule(rule("B3/S23-a"));
+    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+        game.set_cell(x, y, LifeState::Alive);
+    }
+    Engine::step(&mut game, 1);
+    assert_eq!(Engine::population(&game), 0);
+}
+
+#[test]
+fn game_and_hashlife_agree_on_non_totalistic_rules() {
+    for s in ["B2-a/S12", "B3/S2-i34q", "B36/S23-a4ei"] {
+        let (columns, rows) = (48, 48);
+        let mut game = Game::new(columns, rows, 1);
+        game.set_rule(rule(s));
+        let mut hashlife = HashLifeGame::new();
+        hashlife.set_rule(rule(s));
+        let soup = Soup::new(17).with_density(0.3);
+        soup.fill(&mut game, 16, 16, 16, 16);
+        soup.fill(&mut hashlife, 16, 16, 16, 16);
+        for generation in 0..12 {
+            let expected = live_cells(&hashlife);
+            // stop before anything reaches the edges of the bounded grid
+            if expected
+                .iter()
+                .any(|&(x, y)| x <= 0 || y <= 0 || x >= 47 || y >= 47)
+            {
+                break;
+            }
+            assert_eq!(
+                live_cells(&game),
+                expected,
+                "{} generation {}",
+                s,
+                generation
+            );
+            Engine::step(&mut game, 1);
+            Engine::step(&mut hashlife, 1);
+        }
+    }
+}
+
+#[test]
+fn game_matches_dense_on_totalistic_rules() {
+    let (columns, rows) = (40, 30);
+    let mut game = Game::new(columns, rows, 1);
+    let mut dense = DenseGame::new(columns, rows);
+    for engine in [&mut game as &mut dyn Engine, &mut dense] {
+        Soup::new(3)
+            .with_density(0.35)
+            .fill(engine, 0, 0, columns, rows);
+    }
+    for _ in 0..20 {
+        assert_eq!(live_cells(&game), live_cells(&dense));
+        Engine::step(&mut game, 1);
+        Engine::step(&mut dense, 1);
+    }
+}
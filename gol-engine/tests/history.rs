@@ -0,0 +1,177 @@
+mod common;
+
+use common::{snapshot, soup_game};
+use gol_engine::{
+    DenseGame, Engine, Game, HashLifeGame, History, LeniaGame, LeniaRule, LifeState, LtlGame, Rule,
+    RuleTable, SparseGame, Stochastic, TableGame, Update,
+};
+
+const R_PENTOMINO: [(i64, i64); 5] = [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)];
+
+#[test]
+fn rewinding_walks_back_through_every_generation() {
+    let mut game = DenseGame::new(80, 80);
+    for (x, y) in R_PENTOMINO {
+        game.set_cell(40 + x as i32, 40 + y as i32, LifeState::Alive);
+    }
+    let mut history = History::new(1 << 24);
+    let mut seen = vec![snapshot(&game)];
+    for _ in 0..200 {
+        history.step(&mut game, 1);
+        seen.push(snapshot(&game));
+    }
+    assert_eq!(history.len(), 200);
+    while let Some(expected) = seen.pop() {
+        assert_eq!(snapshot(&game), expected);
+        assert_eq!(Engine::generation(&game), seen.len() as u128);
+        history.rewind(&mut game);
+    }
+    assert!(history.is_empty());
+    assert_eq!(history.size(), 0);
+    assert!(!history.rewind(&mut game));
+}
+
+#[test]
+fn dying_states_come_back() {
+    let mut game = Game::new(30, 30, 1);
+    game.set_rule("B2/S345/C4".parse::<Rule>().unwrap());
+    for (x, y) in R_PENTOMINO {
+        game.set_cell(10 + x as i32, 10 + y as i32, LifeState::Alive);
+    }
+    let mut history = History::new(1 << 20);
+    let mut seen = vec![];
+    for _ in 0..10 {
+        seen.push(snapshot(&game));
+        history.step(&mut game, 1);
+    }
+    while let Some(expected) = seen.pop() {
+        history.rewind(&mut game);
+        assert_eq!(snapshot(&game), expected);
+    }
+}
+
+#[test]
+fn big_jumps_rewind_in_one_go() {
+    let mut game = DenseGame::new(80, 80);
+    for (x, y) in R_PENTOMINO {
+        game.set_cell(40 + x as i32, 40 + y as i32, LifeState::Alive);
+    }
+    let start = snapshot(&game);
+    let mut history = History::new(1 << 20);
+    history.step(&mut game, 64);
+    assert_eq!(Engine::generation(&game), 64);
+    assert!(history.rewind(&mut game));
+    assert_eq!(Engine::generation(&game), 0);
+    assert_eq!(snapshot(&game), start);
+}
+
+#[test]
+fn hashlife_and_lenia_arent_recorded() {
+    // listing what a 2^k jump changed would mean going through every cell
+    let mut game = HashLifeGame::new();
+    for (x, y) in R_PENTOMINO {
+        game.set_cell(x, y, LifeState::Alive);
+    }
+    let mut history = History::new(1 << 20);
+    history.step(&mut game, 1024);
+    assert_eq!(Engine::generation(&game), 1024);
+    assert!(history.is_empty());
+    assert!(!history.rewind(&mut game));
+
+    // states round Lenia values, so they couldn't bring them back
+    let mut game = LeniaGame::new(20, 20, LeniaRule::orbium());
+    game.set_cell(10, 10, 0.7);
+    history.step(&mut game, 1);
+    assert!(history.is_empty());
+}
+
+#[test]
+fn changes_are_what_the_step_changed() {
+    fn check(mut engine: impl Engine) {
+        for (x, y) in R_PENTOMINO {
+            engine.set_cell(20 + x, 20 + y, LifeState::Alive);
+        }
+        assert!(engine.can_rewind(), "{}", engine.name());
+        for generations in [1, 1, 3] {
+            let before = snapshot(&engine);
+            let mut changes = engine.step_with_changes(generations).unwrap();
+            let after = snapshot(&engine);
+            let mut expected = before
+                .keys()
+                .chain(after.keys())
+                .filter(|pos| before.get(pos) != after.get(pos))
+                .map(|pos| (*pos, before.get(pos).copied().unwrap_or(LifeState::Dead)))
+                .collect::<Vec<_>>();
+            expected.sort();
+            expected.dedup();
+            changes.sort();
+            assert_eq!(changes, expected, "{}", engine.name());
+        }
+    }
+    check(DenseGame::new(50, 50));
+    check(SparseGame::new());
+    check(Game::new(50, 50, 1));
+    let mut generations = Game::new(50, 50, 1);
+    generations.set_rule("B2/S345/C4".parse::<Rule>().unwrap());
+    check(generations);
+    check(LtlGame::new(
+        50,
+        50,
+        "R2,C0,M1,S2..5,B3..4,NM".parse().unwrap(),
+    ));
+    check(TableGame::new(50, 50, RuleTable::wireworld()));
+}
+
+#[test]
+fn random_runs_replay_the_same_rolls() {
+    // rolls only depend on the seed, the generation and the cell, so stepping again after a rewind
+    // rolls them the same way
+    let stochastic = Stochastic::new(5).with_birth(0.7).with_noise(0.02);
+    for (name, mut game) in [
+        (
+            "stochastic",
+            soup_game(40, 40, 11, 0.4, |game| {
+                game.set_stochastic(Some(stochastic))
+            }),
+        ),
+        (
+            "random-sequential",
+            soup_game(40, 40, 21, 0.35, |game| {
+                game.set_update(Update::RandomSequential, 3)
+            }),
+        ),
+    ] {
+        let mut history = History::new(1 << 24);
+        for _ in 0..10 {
+            history.step(&mut game, 1);
+        }
+        let after = snapshot(&game);
+        for _ in 0..10 {
+            assert!(history.rewind(&mut game), "{}", name);
+        }
+        Engine::step(&mut game, 10);
+        assert_eq!(snapshot(&game), after, "{}", name);
+    }
+}
+
+#[test]
+fn memory_cap_drops_the_oldest_generations() {
+    let mut game = DenseGame::new(80, 80);
+    for (x, y) in R_PENTOMINO {
+        game.set_cell(40 + x as i32, 40 + y as i32, LifeState::Alive);
+    }
+    let mut history = History::new(4096);
+    for _ in 0..300 {
+        history.step(&mut game, 1);
+        assert!(history.size() <= history.capacity());
+    }
+    let kept = history.len();
+    assert!(kept > 0 && kept < 300, "kept {} generations", kept);
+    while history.rewind(&mut game) {}
+    // the rest is gone, so we stop short of the start
+    assert_eq!(Engine::generation(&game), 300 - kept as u128);
+
+    let mut off = History::new(0);
+    off.step(&mut game, 1);
+    assert!(off.is_empty());
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use gol_engine::{Game, Kernel, LifeState, Rule, Topology};
+use std::collections::BTreeSet;
+
+const WEIGHTED: &str = "# sides count double
+1 2 1
+2 0 2
+1 2 1
+";
+
+fn live_cells(game: &Game) -> BTreeSet<(i32, i32)> {
+    game.lifes()
+        .filter(|l| l.state == LifeState::Alive)
+        .map(|l| (l.x, l.y))
+        .collect()
+}
+
+fn soup_game(columns: u32, rows: u32, rule: &str, kernel: Option<Kernel>) -> Game {
+    common::soup_game(columns, rows, 23, 0.35, |game| {
+        game.set_rule(rule.parse().unwrap());
+        game.set_kernel(kernel);
+        game.set_topology(Topology::Torus);
+    })
+}
+
+#[test]
+fn kernels_round_trip() {
+    let kernel: Kernel = WEIGHTED.parse().unwrap();
+    assert_eq!(kernel.max_sum(), 12);
+    assert_eq!(kernel.range(), 1);
+    assert_eq!(kernel.to_string().parse::<Kernel>(), Ok(kernel));
+    let wide: Kernel = "0 0 3 0 0\n0 0 0 0 0\n-1 0 0 0 -1\n0 0 0 0 0\n0 0 3 0 0"
+        .parse()
+        .unwrap();
+    assert_eq!(wide.range(), 2);
+    assert_eq!(wide.max_sum(), 6);
+    assert_eq!(wide.to_string().parse::<Kernel>(), Ok(wide));
+    assert_eq!(
+        Kernel::moore().to_string().parse::<Kernel>(),
+        Ok(Kernel::moore())
+    );
+}
+
+#[test]
+fn bad_kernels_are_rejected() {
+    for kernel in [
+        "1 1\n1 1",
+        "1 1 1\n1 0\n1 1 1",
+        "1 1 1\n1 1 1\n1 1 1",
+        "1 x 1\n1 0 1\n1 1 1",
+        "",
+    ] {
+        assert!(kernel.parse::<Kernel>().is_err(), "{:?}", kernel);
+    }
+}
+
+#[test]
+fn rules_list_sums_past_nine() {
+    let rule: Rule = "B3,10/S2,3".parse().unwrap();
+    assert!(rule.born(10) && rule.born(3) && !rule.born(1));
+    assert!(!rule.born(64));
+    assert_eq!(rule.to_string(), "B3,10/S23");
+    assert_eq!("B12,/S".parse::<Rule>().unwrap().to_string(), "B12,/S");
+    assert_eq!("B3,/S2,3".parse::<Rule>(), "B3/S23".parse::<Rule>());
+    assert!("B3,64/S".parse::<Rule>().is_err());
+}
+
+#[test]
+fn moore_kernel_changes_nothing() {
+    let mut plain = soup_game(30, 20, "B3/S23", None);
+    let mut moore = soup_game(30, 20, "B3/S23", Some(Kernel::moore()));
+    for _ in 0..20 {
+        assert_eq!(live_cells(&plain), live_cells(&moore));
+        plain.step();
+        moore.step();
+    }
+}
+
+#[test]
+fn hexagonal_kernel_matches_hexagonal_rules() {
+    let mut hex = soup_game(30, 20, "B2/S34H", None);
+    let mut kernel = soup_game(30, 20, "B2/S34", Some(Kernel::hexagonal()));
+    for _ in 0..20 {
+        assert_eq!(live_cells(&hex), live_cells(&kernel));
+        hex.step();
+        kernel.step();
+    }
+}
+
+#[test]
+fn von_neumann_kernel_grows_diamonds() {
+    let mut game = Game::new(11, 11, 1);
+    game.set_rule("B12/S01234".parse().unwrap());
+    game.set_kernel(Some(Kernel::von_neumann()));
+    game.set_cell(5, 5, LifeState::Alive);
+    game.step();
+    game.step();
+    let diamond = (0..11)
+        .flat_map(|y| (0..11).map(move |x| (x, y)))
+        .filter(|(x, y): &(i32, i32)| (x - 5).abs() + (y - 5).abs() <= 2)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(live_cells(&game), diamond);
+}
+
+#[test]
+fn weighted_sums_decide() {
+    let kernel: Kernel = WEIGHTED.parse().unwrap();
+    let rule: Rule = "B4,5/S3,4,6,10".parse().unwrap();
+    let (columns, rows) = (24, 18);
+    let mut game = soup_game(columns, rows, &rule.to_string(), Some(kernel.clone()));
+    for generation in 0..15 {
+        let mut expected = BTreeSet::new();
+        for y in 0..rows as i32 {
+            for x in 0..columns as i32 {
+                let sum: i32 = kernel
+                    .weights()
+                    .iter()
+                    .filter(|((dx, dy), _)| {
+                        game.get_cell(
+                            (x + dx).rem_euclid(columns as i32),
+                            (y + dy).rem_euclid(rows as i32),
+                        ) == Some(LifeState::Alive)
+                    })
+                    .map(|(_, w)| w)
+                    .sum();
+                let state = game.get_cell(x, y).unwrap();
+                if rule.next_state(state, sum as usize) == LifeState::Alive {
+                    expected.insert((x, y));
+                }
+            }
+        }
+        game.step();
+        assert_eq!(live_cells(&game), expected, "generation {}", generation);
+    }
+}
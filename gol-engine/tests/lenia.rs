@@ -0,0 +1,138 @@
+use gol_engine::{Engine, Growth, LeniaGame, LeniaRule, LifeState, Rng, Topology};
+
+#[test]
+fn rulestrings_round_trip() {
+    for rule in [
+        "R13,b1,m0.15,s0.015,dt0.1,gaussian",
+        "R10,b1/0.5,m0.26,s0.036,dt0.2,polynomial",
+        "R5,b0/1,m0.3,s0.05,dt1,step",
+    ] {
+        assert_eq!(rule.parse::<LeniaRule>().unwrap().to_string(), rule);
+    }
+    let rule: LeniaRule = "R8,step,s0.1,m0.2".parse().unwrap();
+    assert_eq!(rule.growth_function(), Growth::Step);
+    assert_eq!(rule.dt(), 0.1);
+    assert_eq!(rule.peaks(), &[1.0]);
+}
+
+#[test]
+fn bad_rulestrings_are_rejected() {
+    for bad in [
+        "m0.15,s0.015",
+        "R0,m0.15,s0.015",
+        "R51,m0.15,s0.015",
+        "R13,s0.015",
+        "R13,m0.15",
+        "R13,m0.15,s0",
+        "R13,m0.15,s0.015,dt0",
+        "R13,m0.15,s0.015,dt2",
+        "R13,b0,m0.15,s0.015",
+        "R13,b2,m0.15,s0.015",
+        "R13,m0.15,s0.015,sigmoid",
+        "R13,mx,s0.015",
+    ] {
+        assert!(bad.parse::<LeniaRule>().is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn kernels_are_normalised_rings() {
+    for rule in ["R13,m0.15,s0.015", "R10,b1/0.5/0.25,m0.2,s0.02"] {
+        let rule: LeniaRule = rule.parse().unwrap();
+        let kernel = rule.kernel();
+        let total: f32 = kernel.iter().map(|(_, w)| w).sum();
+        assert!((total - 1.0).abs() < 1e-4, "{}", total);
+        let r = rule.radius() as i32;
+        for &((dx, dy), w) in &kernel {
+            assert!(w > 0.0);
+            assert!(dx * dx + dy * dy <= r * r && (dx, dy) != (0, 0));
+            // the kernel is as round as the grid allows
+            let weight = |pos| kernel.iter().find(|(p, _)| *p == pos).map(|(_, w)| *w);
+            assert_eq!(weight((-dy, dx)), Some(w));
+            assert_eq!(weight((dy, dx)), Some(w));
+        }
+    }
+}
+
+#[test]
+fn growth_functions_peak_at_mu() {
+    for growth in Growth::ALL {
+        let rule: LeniaRule = format!("R5,m0.3,s0.05,{}", growth.name()).parse().unwrap();
+        assert_eq!(rule.growth(0.3), 1.0, "{}", growth.name());
+        assert_eq!(rule.growth(0.9), -1.0, "{}", growth.name());
+        assert!(rule.growth(0.32) >= rule.growth(0.34), "{}", growth.name());
+    }
+    let step: LeniaRule = "R5,m0.3,s0.05,step".parse().unwrap();
+    assert_eq!(step.growth(0.34), 1.0);
+    assert_eq!(step.growth(0.36), -1.0);
+}
+
+#[test]
+fn steps_match_the_naive_convolution() {
+    let rule: LeniaRule = "R4,b1/0.5,m0.2,s0.04,dt0.25".parse().unwrap();
+    let (columns, rows) = (20, 15);
+    let mut game = LeniaGame::new(columns, rows, rule.clone());
+    game.set_topology(Topology::Torus);
+    let mut rng = Rng::new(3);
+    for y in 0..rows as i32 {
+        for x in 0..columns as i32 {
+            game.set_cell(x, y, rng.next_f64() as f32 * 0.6);
+        }
+    }
+    let kernel = rule.kernel();
+    for generation in 0..5 {
+        let mut expected = Vec::new();
+        for y in 0..rows as i32 {
+            for x in 0..columns as i32 {
+                let u: f32 = kernel
+                    .iter()
+                    .map(|((dx, dy), w)| {
+                        w * game
+                            .get_cell(
+                                (x + dx).rem_euclid(columns as i32),
+                                (y + dy).rem_euclid(rows as i32),
+                            )
+                            .unwrap()
+                    })
+                    .sum();
+                let value = game.get_cell(x, y).unwrap();
+                expected.push((value + rule.dt() * rule.growth(u)).clamp(0.0, 1.0));
+            }
+        }
+        game.step();
+        for (i, want) in expected.iter().enumerate() {
+            let got = game
+                .get_cell(i as i32 % columns as i32, i as i32 / columns as i32)
+                .unwrap();
+            assert!((got - want).abs() < 1e-5, "generation {}", generation);
+        }
+    }
+}
+
+#[test]
+fn values_round_to_states() {
+    let mut game = LeniaGame::new(10, 10, LeniaRule::orbium());
+    game.set_cell(1, 1, 1.0);
+    game.set_cell(2, 2, 0.5);
+    game.set_cell(3, 3, 0.001);
+    game.set_cell(4, 4, 7.0);
+    assert_eq!(Engine::get_cell(&game, 1, 1), LifeState::Alive);
+    assert_eq!(Engine::get_cell(&game, 2, 2), LifeState::Dying(127));
+    assert_eq!(Engine::get_cell(&game, 3, 3), LifeState::Dead);
+    assert_eq!(Engine::get_cell(&game, 4, 4), LifeState::Alive);
+    assert_eq!(game.value(3, 3), Some(0.001));
+    assert_eq!(game.value(30, 3), None);
+    assert_eq!(Engine::population(&game), 3);
+    Engine::set_cell(&mut game, 5, 5, LifeState::Dying(127));
+    assert_eq!(game.get_cell(5, 5), Some(0.5));
+    assert!(LifeState::Dying(253).index() < game.states());
+    // an empty grid stays empty, a lone full cell fades away
+    Engine::clear(&mut game);
+    game.step();
+    assert_eq!(game.mass(), 0.0);
+    game.set_cell(5, 5, 1.0);
+    for _ in 0..10 {
+        game.step();
+    }
+    assert_eq!(game.mass(), 0.0);
+}
@@ -0,0 +1,110 @@
+use gol_engine::{DenseGame, Engine, LifeState, LtlGame, LtlRule, Neighborhood, Soup, Topology};
+
+/// Neighborhood count the slow way: look at every cell of the neighborhood
+fn naive_count(game: &LtlGame, rule: &LtlRule, x: i32, y: i32, middle: bool) -> u32 {
+    let r = rule.range() as i32;
+    let mut count = 0;
+    for dy in -r..=r {
+        for dx in -r..=r {
+            if rule.neighborhood() == Neighborhood::VonNeumann && dx.abs() + dy.abs() > r {
+                continue;
+            }
+            if dx == 0 && dy == 0 && !middle {
+                continue;
+            }
+            let wrapped =
+                game.topology()
+                    .wrap(x + dx, y + dy, game.columns() as i32, game.rows() as i32);
+            if wrapped.and_then(|(x, y)| game.get_cell(x, y)) == Some(LifeState::Alive) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+fn assert_matches_naive(rule: &str, middle: bool, topology: Topology) {
+    let rule: LtlRule = rule.parse().unwrap();
+    let (columns, rows) = (37, 29);
+    let mut game = LtlGame::new(columns, rows, rule.clone());
+    game.set_topology(topology);
+    Soup::new(5)
+        .with_density(0.4)
+        .fill(&mut game, 0, 0, columns, rows);
+    for generation in 0..8 {
+        let mut expected = Vec::new();
+        for y in 0..rows as i32 {
+            for x in 0..columns as i32 {
+                let state = game.get_cell(x, y).unwrap();
+                expected.push(rule.next_state(state, naive_count(&game, &rule, x, y, middle)));
+            }
+        }
+        game.step();
+        let got: Vec<_> = (0..rows as i32)
+            .flat_map(|y| (0..columns as i32).map(move |x| (x, y)))
+            .map(|(x, y)| game.get_cell(x, y).unwrap())
+            .collect();
+        assert_eq!(
+            got, expected,
+            "{} on a {} in generation {}",
+            rule, topology, generation
+        );
+    }
+}
+
+#[test]
+fn moore_counts_match_the_naive_ones() {
+    assert_matches_naive("R3,C0,M1,S14..24,B14..19,NM", true, Topology::Torus);
+    assert_matches_naive("R4,C0,M0,S20..40,B25..30,NM", false, Topology::Plane);
+    assert_matches_naive("R2,C4,M1,S6..11,B5..8,NM", true, Topology::Reflective);
+}
+
+#[test]
+fn von_neumann_counts_match_the_naive_ones() {
+    assert_matches_naive("R3,C0,M1,S8..14,B8..10,NN", true, Topology::Torus);
+    assert_matches_naive("R5,C0,M0,S18..30,B20..24,NN", false, Topology::KleinBottle);
+}
+
+#[test]
+fn range_1_is_conways_life() {
+    let mut ltl = LtlGame::new(40, 30, "R1,C0,M0,S2..3,B3..3,NM".parse().unwrap());
+    let mut dense = DenseGame::new(40, 30);
+    ltl.set_topology(Topology::Torus);
+    dense.set_topology(Topology::Torus);
+    let soup = Soup::new(11);
+    soup.fill(&mut ltl, 0, 0, 40, 30);
+    soup.fill(&mut dense, 0, 0, 40, 30);
+    for _ in 0..50 {
+        assert_eq!(ltl.cells_hash(), dense.cells_hash());
+        Engine::step(&mut ltl, 1);
+        Engine::step(&mut dense, 1);
+    }
+}
+
+#[test]
+fn rulestrings_round_trip() {
+    for rule in [
+        "R5,C0,M1,S34..58,B34..45,NM",
+        "R10,C3,M0,S100..200,B90..110,NN",
+    ] {
+        assert_eq!(rule.parse::<LtlRule>().unwrap().to_string(), rule);
+    }
+    // C2 is the same as C0, and the neighborhood defaults to Moore
+    assert_eq!(
+        "r2,c2,m0,s3..5,b4..4"
+            .parse::<LtlRule>()
+            .unwrap()
+            .to_string(),
+        "R2,C0,M0,S3..5,B4..4,NM"
+    );
+    for bad in [
+        "R0,C0,M0,S1..2,B1..2,NM",
+        "C0,M0,S1..2,B1..2,NM",
+        "R2,C0,M0,B1..2,NM",
+        "R2,C0,M0,S1..2,B1..2,NX",
+        "R2,Q1,S1..2,B1..2",
+        "B3/S23",
+    ] {
+        assert!(bad.parse::<LtlRule>().is_err(), "{}", bad);
+    }
+}
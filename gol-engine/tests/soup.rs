@@ -0,0 +1,117 @@
+use gol_engine::{
+    DenseGame, ElementaryGame, ElementaryRule, Engine, Rng, Soup, SparseGame, Symmetry,
+};
+use std::collections::BTreeSet;
+
+#[test]
+fn rng_matches_splitmix64() {
+    // reference values of SplitMix64 seeded with 0, so saved seeds keep meaning the same soups
+    let mut rng = Rng::new(0);
+    assert_eq!(rng.next_u64(), 0xe220a8397b1dcdaf);
+    assert_eq!(rng.next_u64(), 0x6e789e6aa1b965f4);
+    assert_eq!(rng.next_u64(), 0x06c45d188009454f);
+}
+
+#[test]
+fn same_soup_on_every_backend() {
+    let soup = Soup::new(1234).with_density(0.35);
+    let mut dense = DenseGame::new(50, 40);
+    let mut sparse = SparseGame::new();
+    soup.fill(&mut dense, 0, 0, 50, 40);
+    soup.fill(&mut sparse, 0, 0, 50, 40);
+    assert_eq!(dense.cells_hash(), sparse.cells_hash());
+
+    let mut other = SparseGame::new();
+    soup.next().fill(&mut other, 0, 0, 50, 40);
+    assert_ne!(other.cells_hash(), sparse.cells_hash());
+}
+
+#[test]
+fn density_sets_how_full_the_soup_is() {
+    let mut game = SparseGame::new();
+    Soup::new(1)
+        .with_density(0.0)
+        .fill(&mut game, 0, 0, 100, 100);
+    assert_eq!(Engine::population(&game), 0);
+    Soup::new(1)
+        .with_density(1.0)
+        .fill(&mut game, 0, 0, 100, 100);
+    assert_eq!(Engine::population(&game), 10_000);
+    Soup::new(1)
+        .with_density(0.2)
+        .fill(&mut game, 0, 0, 100, 100);
+    let population = Engine::population(&game);
+    assert!((1_700..2_300).contains(&population), "{}", population);
+}
+
+#[test]
+fn fill_size_centres_the_soup_and_clears_the_rest() {
+    let mut game = DenseGame::new(40, 30);
+    Soup::new(9).with_density(1.0).fill(&mut game, 0, 0, 40, 30);
+    Soup::new(9)
+        .with_density(1.0)
+        .with_size(Some(10))
+        .fill(&mut game, 0, 0, 40, 30);
+    assert_eq!(Engine::population(&game), 100);
+    assert_eq!(game.bounding_box(), Some((15, 10, 24, 19)));
+}
+
+#[test]
+fn fill_size_never_spills_out_of_the_area() {
+    let mut game = SparseGame::new();
+    Soup::new(9)
+        .with_density(1.0)
+        .with_size(Some(50))
+        .fill(&mut game, 0, 0, 40, 30);
+    assert_eq!(Engine::bounding_box(&game), Some((0, 0, 39, 29)));
+
+    // a single row, like the top row elementary rules start from
+    let mut row = ElementaryGame::new(40, 30, ElementaryRule::new(30));
+    Soup::new(9)
+        .with_density(1.0)
+        .with_size(Some(10))
+        .fill(&mut row, 0, 0, 40, 1);
+    assert_eq!(Engine::population(&row), 10);
+    assert_eq!(Engine::bounding_box(&row), Some((15, 0, 24, 0)));
+}
+
+#[test]
+fn symmetric_soups_match_their_images() {
+    for symmetry in Symmetry::ALL {
+        let mut game = SparseGame::new();
+        Soup::new(77)
+            .with_symmetry(symmetry)
+            .fill(&mut game, 0, 0, 31, 24);
+        let (columns, rows) = if symmetry.needs_square() {
+            (24, 24)
+        } else {
+            (31, 24)
+        };
+        // C4 and D8 shrink to the square in the middle
+        let offset = (31 - columns as i64) / 2;
+        let cells: BTreeSet<_> = Engine::live_cells(&game)
+            .map(|(x, y)| ((x - offset) as u32, y as u32))
+            .collect();
+        assert!(!cells.is_empty());
+        for &(x, y) in &cells {
+            for image in symmetry.images(x, y, columns, rows) {
+                assert!(
+                    cells.contains(&image),
+                    "{}: {:?} of {:?}",
+                    symmetry,
+                    image,
+                    (x, y)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn symmetries_round_trip_through_their_names() {
+    for symmetry in Symmetry::ALL {
+        assert_eq!(symmetry.to_string().parse::<Symmetry>(), Ok(symmetry));
+    }
+    assert_eq!(Symmetry::D8.next(), Symmetry::C1);
+    assert!("C3".parse::<Symmetry>().is_err());
+}
@@ -0,0 +1,96 @@
+use gol_engine::{
+    DenseGame, Engine, Game, GenerationStats, HashLifeGame, LifeState, Soup, SparseGame,
+    StatsFormat, StatsWriter, StepStats,
+};
+
+#[test]
+fn backends_count_the_same_births_and_deaths() {
+    // the soup stays well inside the bounded grids for 40 generations
+    let mut engines: Vec<Box<dyn Engine>> = vec![
+        Box::new(Game::new(120, 120, 1)),
+        Box::new(DenseGame::new(120, 120)),
+        Box::new(SparseGame::new()),
+    ];
+    for engine in &mut engines {
+        Soup::new(7).fill(engine.as_mut(), 50, 50, 20, 20);
+    }
+    for _ in 0..40 {
+        let before = engines[0].population();
+        let stats: Vec<_> = engines
+            .iter_mut()
+            .map(|engine| {
+                engine.step(1);
+                GenerationStats::of(engine.as_ref())
+            })
+            .collect();
+        assert!(stats.iter().all(|s| *s == stats[0]), "{:?}", stats);
+        let step = stats[0].step.unwrap();
+        assert_eq!(stats[0].population, before + step.births - step.deaths);
+        assert_eq!(step.changed, step.births + step.deaths);
+    }
+}
+
+#[test]
+fn dying_cells_count_as_changed() {
+    let mut game = Game::new(10, 10, 1);
+    game.set_rule("B2/S/C4".parse().unwrap());
+    game.set_cell(4, 4, LifeState::Alive);
+    game.set_cell(4, 5, LifeState::Alive);
+    Engine::step(&mut game, 1);
+    // 2 alive cells start dying, 4 cells on their sides are born
+    assert_eq!(
+        game.last_step(),
+        StepStats {
+            births: 4,
+            deaths: 2,
+            changed: 6
+        }
+    );
+    Engine::step(&mut game, 1);
+    // the old pair keeps dying without being counted as deaths again
+    let step = game.last_step();
+    assert_eq!(step.changed, step.births + step.deaths + 2);
+}
+
+#[test]
+fn json_lines_use_null_for_unknown_values() {
+    let mut game = HashLifeGame::new();
+    let mut writer = StatsWriter::new(Vec::new(), StatsFormat::JsonLines).unwrap();
+    writer.write(&GenerationStats::of(&game)).unwrap();
+    for x in 0..3 {
+        game.set_cell(x, 0, LifeState::Alive);
+    }
+    Engine::step(&mut game, 8);
+    writer.write(&GenerationStats::of(&game)).unwrap();
+    let json = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+        json,
+        "{\"generation\":0,\"population\":0,\"births\":null,\"deaths\":null,\"changed\":null,\"bounding_box\":null}\n\
+         {\"generation\":8,\"population\":3,\"births\":null,\"deaths\":null,\"changed\":null,\"bounding_box\":[0,0,2,0]}\n"
+    );
+}
+
+#[test]
+fn csv_leaves_unknown_values_empty() {
+    let mut writer = StatsWriter::new(Vec::new(), StatsFormat::Csv).unwrap();
+    writer
+        .write(&GenerationStats::of(&HashLifeGame::new()))
+        .unwrap();
+    let csv = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+        csv,
+        "generation,population,births,deaths,changed,min_x,min_y,max_x,max_y\n0,0,,,,,,,\n"
+    );
+}
+
+#[test]
+fn format_follows_the_extension() {
+    for (path, format) in [
+        ("stats.csv", StatsFormat::Csv),
+        ("stats.jsonl", StatsFormat::JsonLines),
+        ("out/stats.json", StatsFormat::JsonLines),
+        ("stats", StatsFormat::Csv),
+    ] {
+        assert_eq!(StatsFormat::from_path(path.as_ref()), format, "{}", path);
+    }
+}
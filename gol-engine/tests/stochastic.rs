@@ -0,0 +1,88 @@
+mod common;
+
+use common::snapshot;
+use gol_engine::{Game, LifeState, Rule, Stochastic};
+
+fn soup_game(stochastic: Option<Stochastic>) -> Game {
+    common::soup_game(40, 40, 11, 0.4, |game| game.set_stochastic(stochastic))
+}
+
+#[test]
+fn certain_chances_follow_the_rule() {
+    let mut plain = soup_game(None);
+    let mut stochastic = soup_game(Some(Stochastic::new(3)));
+    assert!(Stochastic::new(3).is_deterministic());
+    for _ in 0..30 {
+        assert_eq!(snapshot(&plain), snapshot(&stochastic));
+        plain.step();
+        stochastic.step();
+    }
+}
+
+#[test]
+fn same_seed_same_run() {
+    let stochastic = Stochastic::new(42)
+        .with_birth(0.8)
+        .with_survival(0.9)
+        .with_noise(0.01);
+    assert!(!stochastic.is_deterministic());
+    let mut a = soup_game(Some(stochastic));
+    let mut b = soup_game(Some(stochastic));
+    b.set_threads(4);
+    let mut c = soup_game(Some(Stochastic {
+        seed: 43,
+        ..stochastic
+    }));
+    for _ in 0..20 {
+        a.step();
+        b.step();
+        c.step();
+        assert_eq!(snapshot(&a), snapshot(&b));
+    }
+    assert_ne!(snapshot(&a), snapshot(&c));
+}
+
+#[test]
+fn failed_rolls_keep_cells_as_they_are() {
+    // nothing is ever born, so the population only shrinks
+    let mut game = soup_game(Some(Stochastic::new(1).with_birth(0.0)));
+    for _ in 0..20 {
+        let before = snapshot(&game);
+        game.step();
+        assert!(snapshot(&game).keys().all(|cell| before.contains_key(cell)));
+    }
+    // nothing survives, cells live a single generation
+    let mut game = soup_game(Some(Stochastic::new(1).with_survival(0.0)));
+    for _ in 0..20 {
+        let before = snapshot(&game);
+        game.step();
+        assert!(snapshot(&game)
+            .keys()
+            .all(|cell| !before.contains_key(cell)));
+    }
+    // dying states still count down when survivals fail
+    let mut game = Game::new(10, 10, 1);
+    game.set_rule("B2/S345/C4".parse::<Rule>().unwrap());
+    game.set_stochastic(Some(Stochastic::new(1).with_survival(0.0)));
+    game.set_cell(5, 5, LifeState::Alive);
+    game.step();
+    assert_eq!(game.get_cell(5, 5), Some(LifeState::Dying(1)));
+}
+
+#[test]
+fn noise_flips_its_share_of_cells() {
+    let mut game = Game::new(100, 100, 1);
+    game.set_stochastic(Some(Stochastic::new(9).with_noise(0.1)));
+    game.step();
+    let population = game.population();
+    assert!((800..1200).contains(&population), "{}", population);
+    // with B/S nothing is born nor survives, noise alone keeps the grid going
+    game.set_rule("B/S".parse::<Rule>().unwrap());
+    game.step();
+    let population = game.population();
+    assert!((800..1200).contains(&population), "{}", population);
+    assert_eq!(
+        Stochastic::new(9).with_noise(2.0).to_string(),
+        "seed=9 birth=1 survival=1 noise=1"
+    );
+}
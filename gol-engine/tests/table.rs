@@ -0,0 +1,187 @@
+use gol_engine::{Engine, Game, LifeState, RuleTable, Soup, TableGame, Topology};
+use std::collections::BTreeSet;
+
+const LIFE: &str = "@RULE Life
+@TABLE
+n_states:2
+neighborhood:Moore
+symmetries:permute
+var a={0,1}
+var b={0,1}
+var c={0,1}
+var d={0,1}
+var e={0,1}
+var f={0,1}
+var g={0,1}
+var h={0,1}
+0,1,1,1,0,0,0,0,0,1
+1,1,1,0,0,0,0,0,0,1
+1,1,1,1,0,0,0,0,0,1
+1,a,b,c,d,e,f,g,h,0
+";
+
+/// Table with a single transition: a cell in state 0 with a 1 above it becomes a 1
+fn one_transition(symmetries: &str) -> RuleTable {
+    format!(
+        "@TABLE\nn_states:2\nneighborhood:Moore\nsymmetries:{}\n0,1,0,0,0,0,0,0,0,1",
+        symmetries
+    )
+    .parse()
+    .unwrap()
+}
+
+#[test]
+fn electrons_run_along_wires() {
+    let mut game = TableGame::new(12, 5, RuleTable::wireworld());
+    for x in 0..12 {
+        game.set_cell(x, 2, 3);
+    }
+    game.set_cell(0, 2, 2);
+    game.set_cell(1, 2, 1);
+    for generation in 1..8 {
+        game.step();
+        let wire = (0..12)
+            .map(|x| game.get_cell(x, 2).unwrap())
+            .collect::<Vec<_>>();
+        let mut expected = vec![3; 12];
+        expected[generation] = 2;
+        expected[generation + 1] = 1;
+        assert_eq!(wire, expected, "generation {}", generation);
+    }
+    assert_eq!(game.population(), 12);
+    assert_eq!(Engine::rule_name(&game), "WireWorld");
+}
+
+#[test]
+fn three_heads_leave_a_conductor_alone() {
+    let wireworld = RuleTable::wireworld();
+    assert_eq!(wireworld.next_state(&[3, 1, 0, 0, 1, 0, 0, 0, 0]), 1);
+    assert_eq!(wireworld.next_state(&[3, 0, 0, 1, 0, 0, 0, 0, 0]), 1);
+    assert_eq!(wireworld.next_state(&[3, 1, 1, 0, 0, 1, 0, 0, 0]), 3);
+    assert_eq!(wireworld.next_state(&[3, 0, 0, 0, 0, 0, 0, 0, 0]), 3);
+    assert_eq!(wireworld.next_state(&[0, 1, 1, 0, 0, 0, 0, 0, 0]), 0);
+    assert_eq!(wireworld.color(1), Some((0, 128, 255)));
+}
+
+#[test]
+fn symmetries_expand_transitions() {
+    for (symmetries, transitions) in [
+        ("none", 1),
+        ("reflect_horizontal", 1),
+        ("rotate4", 4),
+        ("rotate4reflect", 4),
+        ("rotate8", 8),
+        ("permute", 1),
+    ] {
+        let table = one_transition(symmetries);
+        assert_eq!(table.transitions(), transitions, "{}", symmetries);
+        assert_eq!(table.next_state(&[0, 1, 0, 0, 0, 0, 0, 0, 0]), 1);
+    }
+    let rotate4 = one_transition("rotate4");
+    assert_eq!(rotate4.next_state(&[0, 0, 0, 0, 0, 0, 0, 1, 0]), 1);
+    assert_eq!(rotate4.next_state(&[0, 0, 0, 0, 0, 0, 0, 0, 1]), 0);
+    assert_eq!(
+        one_transition("rotate8").next_state(&[0, 0, 0, 0, 0, 0, 0, 0, 1]),
+        1
+    );
+}
+
+#[test]
+fn permute_matches_neighbors_in_any_order() {
+    let table: RuleTable = "@TABLE
+n_states:9
+neighborhood:Moore
+symmetries:permute
+0,1,2,3,4,5,6,7,8,1"
+        .parse()
+        .unwrap();
+    assert_eq!(table.transitions(), 1);
+    assert_eq!(table.next_state(&[0, 1, 2, 3, 4, 5, 6, 7, 8]), 1);
+    assert_eq!(table.next_state(&[0, 8, 3, 5, 1, 7, 2, 6, 4]), 1);
+    assert_eq!(table.next_state(&[0, 8, 3, 5, 1, 7, 2, 6, 6]), 0);
+    assert_eq!(table.next_state(&[1, 8, 3, 5, 1, 7, 2, 6, 4]), 1);
+}
+
+#[test]
+fn variables_used_twice_are_bound() {
+    let table: RuleTable = "@TABLE
+n_states:3
+neighborhood:vonNeumann
+var a={1,2}
+var b={0,1,2}
+var c={0,1,2}
+0,a,a,b,c,a
+"
+    .parse()
+    .unwrap();
+    assert_eq!(table.next_state(&[0, 1, 1, 0, 2]), 1);
+    assert_eq!(table.next_state(&[0, 2, 2, 1, 1]), 2);
+    assert_eq!(table.next_state(&[0, 1, 2, 0, 0]), 0);
+    let compact: RuleTable = "@TABLE\nn_states:2\nneighborhood:vonNeumann\n010001"
+        .parse()
+        .unwrap();
+    assert_eq!(compact.next_state(&[0, 1, 0, 0, 0]), 1);
+}
+
+#[test]
+fn colors_and_gradients() {
+    let table: RuleTable = "@TABLE
+n_states:5
+neighborhood:Moore
+@COLORS
+0 10 20 30
+0 0 0 200 200 200
+"
+    .parse()
+    .unwrap();
+    assert_eq!(table.color(0), Some((10, 20, 30)));
+    assert_eq!(table.color(1), Some((0, 0, 0)));
+    assert_eq!(table.color(4), Some((200, 200, 200)));
+    assert_eq!(table.color(5), None);
+    assert_eq!(one_transition("none").color(1), None);
+}
+
+#[test]
+fn bad_tables_are_rejected() {
+    for table in [
+        "@TABLE\nn_states:2\nneighborhood:Moore\n0,2,0,0,0,0,0,0,0,1",
+        "@TABLE\nn_states:2\nneighborhood:Moore\n0,x,0,0,0,0,0,0,0,1",
+        "@TABLE\nn_states:2\nneighborhood:Moore\n0,1,0,1",
+        "@TABLE\nn_states:2\nneighborhood:Moore\nvar a={0,1}\n0,a,0,0,0,0,0,0,0,b",
+        "@TABLE\nn_states:2\nneighborhood:Moore\nvar a={0,1}\nvar b={0,1}\n0,a,0,0,0,0,0,0,0,b",
+        "@TABLE\nn_states:2\nneighborhood:vonNeumann\nsymmetries:rotate8\n0,1,0,0,0,1",
+        "@TABLE\nn_states:2\nneighborhood:hexagonal",
+        "@TABLE\nn_states:300\nneighborhood:Moore",
+        "@TABLE\n0,1,0,0,0,0,0,0,0,1",
+        "@RULE Nothing",
+    ] {
+        assert!(table.parse::<RuleTable>().is_err(), "{:?}", table);
+    }
+}
+
+#[test]
+fn life_table_matches_game() {
+    let (columns, rows) = (30, 20);
+    let mut table = TableGame::new(columns, rows, LIFE.parse().unwrap());
+    table.set_topology(Topology::Torus);
+    let mut game = Game::new(columns, rows, 1);
+    game.set_topology(Topology::Torus);
+    let soup = Soup::new(5).with_density(0.4);
+    soup.fill(&mut table, 0, 0, columns, rows);
+    soup.fill(&mut game, 0, 0, columns, rows);
+    for generation in 0..20 {
+        let alive = game
+            .lifes()
+            .filter(|l| l.state == LifeState::Alive)
+            .map(|l| (l.x as i64, l.y as i64))
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            table.live_cells().collect::<BTreeSet<_>>(),
+            alive,
+            "generation {}",
+            generation
+        );
+        Engine::step(&mut table, 1);
+        game.step();
+    }
+}
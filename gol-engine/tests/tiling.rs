@@ -0,0 +1,148 @@
+use gol_engine::{Game, LifeState, Rule, Soup, Tiling, Topology};
+use std::collections::BTreeSet;
+
+fn neighbor_set(tiling: Tiling, x: i32, y: i32) -> BTreeSet<(i64, i64)> {
+    tiling
+        .neighbors(x, y)
+        .iter()
+        .map(|(dx, dy)| ((x + dx) as i64, (y + dy) as i64))
+        .collect()
+}
+
+/// Cells found on a small circle of `radius` around (`cx`, `cy`) of the drawing
+fn cells_around(tiling: Tiling, cx: f64, cy: f64, radius: f64) -> BTreeSet<(i64, i64)> {
+    (0..360)
+        .map(|degrees| (degrees as f64).to_radians())
+        .map(|a| tiling.cell_at(cx + radius * a.cos(), cy + radius * a.sin()))
+        .collect()
+}
+
+#[test]
+fn neighbors_are_mutual() {
+    for tiling in Tiling::ALL {
+        for y in -3..3 {
+            for x in -3..3 {
+                assert_eq!(tiling.neighbors(x, y).len(), tiling.max_neighbors());
+                for &(nx, ny) in &neighbor_set(tiling, x, y) {
+                    assert!(
+                        neighbor_set(tiling, nx as i32, ny as i32).contains(&(x as i64, y as i64)),
+                        "{} ({}, {}) and ({}, {})",
+                        tiling,
+                        x,
+                        y,
+                        nx,
+                        ny
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn hexagons_touch_their_neighbors() {
+    for (x, y) in [(0, 0), (3, 1), (-2, 5), (4, -3)] {
+        let center = (x as f64 + 0.5 - y as f64 / 2.0, y as f64 + 0.5);
+        assert_eq!(Tiling::Hexagonal.cell_at(center.0, center.1), (x, y));
+        let mut touching = cells_around(Tiling::Hexagonal, center.0, center.1, 0.8);
+        touching.remove(&(x, y));
+        assert_eq!(
+            touching,
+            neighbor_set(Tiling::Hexagonal, x as i32, y as i32)
+        );
+    }
+}
+
+#[test]
+fn triangles_touch_their_neighbors() {
+    for (x, y) in [(0, 0), (1, 0), (3, 2), (-2, 5), (-3, -1)] {
+        let left = x as f64 / 2.0;
+        let up = (x + y) % 2 == 0;
+        let (top, bottom) = (y as f64, y as f64 + 1.0);
+        let (corners, centroid) = if up {
+            (
+                [(left, bottom), (left + 1.0, bottom), (left + 0.5, top)],
+                (left + 0.5, top + 2.0 / 3.0),
+            )
+        } else {
+            (
+                [(left, top), (left + 1.0, top), (left + 0.5, bottom)],
+                (left + 0.5, top + 1.0 / 3.0),
+            )
+        };
+        assert_eq!(Tiling::Triangular.cell_at(centroid.0, centroid.1), (x, y));
+        let mut touching = BTreeSet::new();
+        for (cx, cy) in corners {
+            touching.extend(cells_around(Tiling::Triangular, cx, cy, 0.01));
+        }
+        touching.remove(&(x, y));
+        assert_eq!(
+            touching,
+            neighbor_set(Tiling::Triangular, x as i32, y as i32),
+            "({}, {})",
+            x,
+            y
+        );
+    }
+}
+
+#[test]
+fn tiling_rules_round_trip() {
+    for s in ["B2/S34H", "B246/S0H", "B4/S345L", "B3a/S9bcL", "B2/S34H/C3"] {
+        assert_eq!(s.parse::<Rule>().unwrap().to_string(), s);
+    }
+    assert_eq!(
+        "b2/s34h".parse::<Rule>().unwrap().tiling(),
+        Tiling::Hexagonal
+    );
+    assert_eq!(
+        "B4/S345L".parse::<Rule>().unwrap().tiling(),
+        Tiling::Triangular
+    );
+    assert_eq!("B3/S23".parse::<Rule>().unwrap().tiling(), Tiling::Square);
+    // hexagons have 6 neighbors at most and no Hensel letters
+    for s in ["B7/S34H", "B2a/S34H", "B4/S3dL"] {
+        assert!(s.parse::<Rule>().is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn game_counts_neighbors_of_the_tiling() {
+    for (rule, columns, rows) in [("B2/S34H", 30, 20), ("B45/S34567L", 40, 20)] {
+        let rule: Rule = rule.parse().unwrap();
+        let mut game = Game::new(columns, rows, 1);
+        game.set_rule(rule);
+        game.set_topology(Topology::Torus);
+        Soup::new(11)
+            .with_density(0.3)
+            .fill(&mut game, 0, 0, columns, rows);
+        for generation in 0..10 {
+            let alive = |x: i32, y: i32| {
+                game.get_cell(x.rem_euclid(columns as i32), y.rem_euclid(rows as i32))
+                    == Some(LifeState::Alive)
+            };
+            let mut expected = BTreeSet::new();
+            for y in 0..rows as i32 {
+                for x in 0..columns as i32 {
+                    let n = rule
+                        .tiling()
+                        .neighbors(x, y)
+                        .iter()
+                        .filter(|(dx, dy)| alive(x + dx, y + dy))
+                        .count();
+                    let state = game.get_cell(x, y).unwrap();
+                    if rule.next_state(state, n) == LifeState::Alive {
+                        expected.insert((x, y));
+                    }
+                }
+            }
+            game.step();
+            let cells: BTreeSet<_> = game
+                .lifes()
+                .filter(|l| l.state == LifeState::Alive)
+                .map(|l| (l.x, l.y))
+                .collect();
+            assert_eq!(cells, expected, "{} generation {}", rule, generation);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use gol_engine::{Game, LifeState, Topology};
+use std::collections::BTreeSet;
+
+/// Glider heading south east (+1, +1 every 4 generations)
+const GLIDER: [(i32, i32); 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+fn game_with(topology: Topology, columns: u32, rows: u32, cells: &[(i32, i32)]) -> Game {
+    let mut game = Game::new(columns, rows, 1);
+    game.set_topology(topology);
+    for &(x, y) in cells {
+        game.set_cell(x, y, LifeState::Alive);
+    }
+    game
+}
+
+fn live_cells(game: &Game) -> BTreeSet<(i32, i32)> {
+    game.lifes()
+        .filter(|l| l.state == LifeState::Alive)
+        .map(|l| (l.x, l.y))
+        .collect()
+}
+
+/// Glider placed at `(x, y)` after `4 * k` generations on an infinite plane
+fn glider_after(x: i32, y: i32, k: i32) -> impl Iterator<Item = (i32, i32)> {
+    GLIDER.iter().map(move |(gx, gy)| (gx + x + k, gy + y + k))
+}
+
+fn run(game: &mut Game, generations: u32) {
+    for _ in 0..generations {
+        game.step();
+    }
+}
+
+#[test]
+fn plane_edges_kill_gliders() {
+    let mut game = game_with(
+        Topology::Plane,
+        10,
+        10,
+        &glider_after(5, 5, 0).collect::<Vec<_>>(),
+    );
+    run(&mut game, 40);
+    assert_eq!(
+        game.population(),
+        4,
+        "glider should end up as a block in the corner"
+    );
+}
+
+#[test]
+fn torus_glider_wraps_with_same_orientation() {
+    let mut game = game_with(
+        Topology::Torus,
+        16,
+        10,
+        &glider_after(12, 6, 0).collect::<Vec<_>>(),
+    );
+    run(&mut game, 24);
+    let expected = glider_after(12, 6, 6)
+        .map(|(x, y)| (x.rem_euclid(16), y.rem_euclid(10)))
+        .collect();
+    assert_eq!(live_cells(&game), expected);
+}
+
+#[test]
+fn klein_bottle_mirrors_across_twisted_edge() {
+    let mut game = game_with(
+        Topology::KleinBottle,
+        20,
+        10,
+        &glider_after(5, 5, 0).collect::<Vec<_>>(),
+    );
+    run(&mut game, 24);
+    // crossed the bottom edge: comes back from the top flipped left to right (heading south west)
+    let expected: BTreeSet<_> = glider_after(5, 5, 6)
+        .map(|(x, y)| (19 - x, y - 10))
+        .collect();
+    assert_eq!(live_cells(&game), expected);
+    run(&mut game, 4);
+    let expected = expected.iter().map(|(x, y)| (x - 1, y + 1)).collect();
+    assert_eq!(live_cells(&game), expected);
+}
+
+#[test]
+fn klein_bottle_keeps_orientation_across_straight_edge() {
+    let mut game = game_with(
+        Topology::KleinBottle,
+        10,
+        20,
+        &glider_after(5, 5, 0).collect::<Vec<_>>(),
+    );
+    run(&mut game, 24);
+    let expected = glider_after(5, 5, 6).map(|(x, y)| (x - 10, y)).collect();
+    assert_eq!(live_cells(&game), expected);
+}
+
+#[test]
+fn cross_surface_mirrors_across_both_edges() {
+    // over the right edge: flipped top to bottom (heading north east)
+    let mut game = game_with(
+        Topology::CrossSurface,
+        20,
+        20,
+        &glider_after(14, 4, 0).collect::<Vec<_>>(),
+    );
+    run(&mut game, 24);
+    let expected: BTreeSet<_> = glider_after(14, 4, 6)
+        .map(|(x, y)| (x - 20, 19 - y))
+        .collect();
+    assert_eq!(live_cells(&game), expected);
+    run(&mut game, 4);
+    let expected = expected.iter().map(|(x, y)| (x + 1, y - 1)).collect();
+    assert_eq!(live_cells(&game), expected);
+
+    // over the bottom edge: flipped left to right (heading south west)
+    let mut game = game_with(
+        Topology::CrossSurface,
+        20,
+        20,
+        &glider_after(4, 14, 0).collect::<Vec<_>>(),
+    );
+    run(&mut game, 24);
+    let expected = glider_after(4, 14, 6)
+        .map(|(x, y)| (19 - x, y - 20))
+        .collect();
+    assert_eq!(live_cells(&game), expected);
+}
+
+#[test]
+fn sphere_turns_gliders_a_quarter() {
+    // over the bottom edge: comes out of the right edge heading south west
+    let mut game = game_with(
+        Topology::Sphere,
+        16,
+        16,
+        &glider_after(3, 10, 0).collect::<Vec<_>>(),
+    );
+    run(&mut game, 24);
+    let expected: BTreeSet<_> = glider_after(3, 10, 6).map(|(x, y)| (31 - y, x)).collect();
+    assert_eq!(live_cells(&game), expected);
+    run(&mut game, 4);
+    let expected = expected.iter().map(|(x, y)| (x - 1, y + 1)).collect();
+    assert_eq!(live_cells(&game), expected);
+
+    // over the right edge: comes out of the bottom edge heading north east
+    let mut game = game_with(
+        Topology::Sphere,
+        16,
+        16,
+        &glider_after(10, 3, 0).collect::<Vec<_>>(),
+    );
+    run(&mut game, 24);
+    let expected = glider_after(10, 3, 6).map(|(x, y)| (y, 31 - x)).collect();
+    assert_eq!(live_cells(&game), expected);
+}
+
+#[test]
+fn reflective_edges_behave_like_a_mirrored_torus() {
+    // a reflective grid evolves exactly like one quadrant of a torus twice its size holding 4 mirrored copies
+    let (columns, rows) = (12, 9);
+    let cells: Vec<_> = glider_after(6, 2, 0)
+        .chain([(0, 7), (1, 7), (1, 8)])
+        .collect();
+    let mirrored: Vec<_> = cells
+        .iter()
+        .flat_map(|&(x, y)| {
+            [
+                (x, y),
+                (2 * columns - 1 - x, y),
+                (x, 2 * rows - 1 - y),
+                (2 * columns - 1 - x, 2 * rows - 1 - y),
+            ]
+        })
+        .collect();
+    let mut reflective = game_with(Topology::Reflective, columns as u32, rows as u32, &cells);
+    let mut torus = game_with(
+        Topology::Torus,
+        2 * columns as u32,
+        2 * rows as u32,
+        &mirrored,
+    );
+    for generation in 0..40 {
+        let quadrant: BTreeSet<_> = live_cells(&torus)
+            .into_iter()
+            .filter(|&(x, y)| x < columns && y < rows)
+            .collect();
+        assert_eq!(
+            live_cells(&reflective),
+            quadrant,
+            "generation {}",
+            generation
+        );
+        reflective.step();
+        torus.step();
+    }
+}
+
+#[test]
+fn topologies_round_trip_through_their_names() {
+    for topology in Topology::ALL {
+        assert_eq!(topology.to_string().parse::<Topology>(), Ok(topology));
+    }
+    assert!("donut".parse::<Topology>().is_err());
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::snapshot;
+use gol_engine::{Game, LifeState, Stochastic, Update};
+
+const SCHEMES: [Update; 4] = [
+    Update::Synchronous,
+    Update::RandomSequential,
+    Update::RandomOrder,
+    Update::Alpha(0.5),
+];
+
+fn soup_game(update: Update, seed: u64) -> Game {
+    common::soup_game(40, 40, 21, 0.35, |game| game.set_update(update, seed))
+}
+
+#[test]
+fn names_round_trip() {
+    for update in SCHEMES {
+        assert_eq!(update.to_string().parse::<Update>(), Ok(update));
+    }
+    assert_eq!("sync".parse::<Update>(), Ok(Update::Synchronous));
+    for bad in ["alpha", "alpha:", "alpha:1.5", "alpha:-0.1", "random"] {
+        assert!(bad.parse::<Update>().is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn alpha_bounds_are_synchronous_and_frozen() {
+    let mut sync = soup_game(Update::Synchronous, 0);
+    let mut one = soup_game(Update::Alpha(1.0), 4);
+    let mut zero = soup_game(Update::Alpha(0.0), 4);
+    let start = snapshot(&zero);
+    for _ in 0..20 {
+        sync.step();
+        one.step();
+        zero.step();
+        assert_eq!(snapshot(&sync), snapshot(&one));
+        assert_eq!(snapshot(&zero), start);
+    }
+}
+
+#[test]
+fn still_lifes_stay_still_under_every_scheme() {
+    for update in SCHEMES {
+        let mut game = Game::new(10, 10, 1);
+        game.set_update(update, 8);
+        for (x, y) in [(3, 3), (4, 3), (3, 4), (4, 4)] {
+            game.set_cell(x, y, LifeState::Alive);
+        }
+        let block = snapshot(&game);
+        for _ in 0..10 {
+            game.step();
+            assert_eq!(snapshot(&game), block, "{}", update);
+        }
+    }
+}
+
+#[test]
+fn seeds_decide_the_run() {
+    for update in [
+        Update::RandomSequential,
+        Update::RandomOrder,
+        Update::Alpha(0.5),
+    ] {
+        let mut a = soup_game(update, 1);
+        let mut b = soup_game(update, 1);
+        b.set_threads(4);
+        let mut c = soup_game(update, 2);
+        let mut sync = soup_game(Update::Synchronous, 1);
+        for _ in 0..10 {
+            let population = a.population() as i64;
+            a.step();
+            b.step();
+            c.step();
+            sync.step();
+            assert_eq!(snapshot(&a), snapshot(&b), "{}", update);
+            // births and deaths are counted against the last generation, not every single update
+            let stats = a.last_step();
+            assert_eq!(
+                a.population() as i64 - population,
+                stats.births as i64 - stats.deaths as i64,
+                "{}",
+                update
+            );
+        }
+        assert_ne!(snapshot(&a), snapshot(&c), "{}", update);
+        assert_ne!(snapshot(&a), snapshot(&sync), "{}", update);
+    }
+}
+
+#[test]
+fn noise_reaches_the_cells_alpha_skips() {
+    let mut game = Game::new(100, 100, 1);
+    // with B/S nothing is born nor survives, noise alone keeps the grid going
+    game.set_rule("B/S".parse().unwrap());
+    game.set_update(Update::Alpha(0.5), 6);
+    game.set_stochastic(Some(Stochastic::new(9).with_noise(0.1)));
+    game.step();
+    // a tenth of every cell, not a tenth of the half that was updated
+    let population = game.population();
+    assert!((800..1200).contains(&population), "{}", population);
+}
@@ -71,8 +71,8 @@ fn parsery(time: &str) -> std::time::Duration {
 }
 
 impl VideoRecorder {
-    /// Spawns new instance of FFMpeg with out file, size and FPS
-    pub fn new(out: &str, width: u32, height: u32, fps: u32) -> Self {
+    /// Spawns new instance of FFMpeg with out file, size and FPS. `comment` is saved in the video's metadata
+    pub fn new(out: &str, width: u32, height: u32, fps: u32, comment: &str) -> Self {
         let mut ffmpeg_cmd = std::process::Command::new("ffmpeg")
             .args([
                 "-hide_banner",
@@ -93,6 +93,8 @@ impl VideoRecorder {
                 "-preset",
                 "veryslow",
                 "-y",
+                "-metadata",
+                &format!("comment={}", comment),
                 "-progress",
                 "pipe:1",
                 out,
@@ -1,17 +1,40 @@
 // #![windows_subsystem = "windows"]
-use crate::core::{Game, Life, LifeState};
-use crate::utils::{truncate, word_wrap};
-use clap::Parser;
+use crate::utils::{
+    format_big_number, noise_text, paint_text, soup_text, stability_text, truncate, word_wrap,
+};
+use clap::{CommandFactory, Parser};
 /// timelessnesses' implementation of Conway's Game Of Life in SDL2.
-use std::collections::HashMap;
-use utils::{create_grid_texture, render_text_as_texture};
+use gol_engine::{
+    Colours, CycleDetector, DenseGame, ElementaryGame, ElementaryRule, Engine, Game,
+    GenerationStats, HashLifeGame, History, Kernel, LeniaGame, LeniaRule, LifeState, LtlGame,
+    LtlRule, Rule, RuleTable, Soup, SparseGame, StatsFormat, StatsWriter, Stochastic, Symmetry,
+    TableGame, Tiling, Topology, Update,
+};
+use utils::{
+    create_grid_texture, life_state_color, render_text_as_texture, value_color, Board, TEAM_COLORS,
+};
 
-mod core;
 mod ffmpeg;
 mod utils;
 
+/// Simulation backend picked with `--engine`
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum EngineKind {
+    /// HashMap grid the size of the window. Slow, but the only one with Generations (dying) states
+    Game,
+    /// Bit-packed grid the size of the window
+    Dense,
+    /// Endless universe keeping track of alive cells only
+    Sparse,
+    /// Endless universe stepped with HashLife. Made for guns, breeders and other huge patterns
+    #[value(name = "hashlife")]
+    HashLife,
+}
+
 #[derive(clap::Parser)]
 #[command(author = "timelessnesses", about = "Nothing")]
+// what tells --headless when to stop
+#[command(group(clap::ArgGroup::new("stop").args(["generations", "stop_when_stable"]).multiple(true)))]
 struct Cli {
     /// List GPU renderers (for the SELECTED_GPU_RENDERER arg)
     #[arg(long)]
@@ -51,8 +74,196 @@ struct Cli {
     /// How long until next simulation (in milliseconds)
     #[arg(short, long, default_value_t = 250)]
     next_simulation: u64,
+
+    /// Birth/survival rulestring (e.g. B3/S23, B36/S23, 23/3 or B2/S). Generations rules take a /C<n> suffix (e.g. B2/S345/C4).
+    /// Counts can be narrowed down with Hensel letters (e.g. B2-a/S12 or B3/S2-i34q).
+    /// Hexagonal rules end with H (e.g. B2/S34H) and triangular ones with L (e.g. B4/S345L, counts past 9 are a, b and c)
+    /// Sums of --kernel weights past 9 are written as comma separated lists (e.g. B3,5/S4,6,7,10)
+    /// Larger than Life rules use Golly's notation (e.g. R5,C0,M1,S34..58,B34..45,NM)
+    /// Elementary 1D rules are a W and Wolfram's number (e.g. W30 or W110), drawn one generation per row
+    #[arg(long, default_value = "B3/S23")]
+    rule: RuleArg,
+
+    /// What's past the grid edges: plane (always dead), torus, klein-bottle, cross-surface, sphere (square grids only) or reflective
+    #[arg(long, default_value = "plane")]
+    topology: Topology,
+
+    /// Neighbors the rule looks at: moore, von-neumann, hexagonal or a file of integer weights ("weighted life",
+    /// see the README). The rule then decides on the sum of the weights of the alive neighbors (game engine only)
+    #[arg(long, value_parser = parse_kernel)]
+    kernel: Option<Kernel>,
+
+    /// Run a rule table instead of --rule: wireworld or the path of a Golly .rule file (its @TABLE and
+    /// @COLORS sections). The number keys pick the state cells are drawn with
+    #[arg(long, value_parser = parse_rule_table)]
+    rule_table: Option<RuleTable>,
+
+    /// Run Lenia instead of --rule: cells hold a value from 0 to 1 grown by a ring kernel. orbium or
+    /// R<radius>,b<ring heights, e.g. 1/0.5>,m<mu>,s<sigma>,dt<time step>,<gaussian, polynomial or step growth>
+    #[arg(long, value_parser = parse_lenia)]
+    lenia: Option<LeniaRule>,
+
+    /// Split alive cells between teams: single, immigration (2 teams) or quadlife (4 teams). Newborns join
+    /// the team most of their parents are in, the number keys pick the team cells are drawn with (game engine only)
+    #[arg(long, default_value = "single")]
+    colours: Colours,
+
+    /// Draw hexagonal rules on the skewed square grid Golly uses instead of as hexagons
+    #[arg(long, default_value_t = false)]
+    skewed_hex: bool,
+
+    /// Simulation backend (dense unless asked otherwise, game is the slower reference it's tested against).
+    /// sparse and hashlife are endless (they ignore --topology and the arrow keys move the view around)
+    #[arg(long, value_enum, default_value_t = EngineKind::Dense)]
+    engine: EngineKind,
+
+    /// Every simulation step jumps 2^STEP_EXPONENT generations (best used with --engine hashlife)
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=56))]
+    step_exponent: u8,
+
+    /// How much memory (in MiB) the rewind history may use. The oldest generations are forgotten past that (0 turns rewinding off).
+    /// The hashlife engine and Lenia can't be rewound
+    #[arg(long, default_value_t = 64)]
+    history_memory: usize,
+
+    /// Stop once the grid starts repeating itself (ends the recording too)
+    #[arg(long, default_value_t = false)]
+    stop_when_stable: bool,
+
+    /// Write population, births, deaths, changed cells and bounding box of every simulation step to this file
+    /// (JSON lines if it ends with .json or .jsonl, CSV otherwise). The first row is the generation the
+    /// simulation started from. Births, deaths and changed cells are left empty for the engines that don't
+    /// count them (hashlife, Larger than Life, rule tables, Lenia and elementary rules)
+    #[arg(long)]
+    stats_out: Option<std::path::PathBuf>,
+
+    /// Run without a window, starting from a random grid the size of the window (needs --generations or --stop-when-stable)
+    #[arg(long, default_value_t = false, requires = "stop")]
+    headless: bool,
+
+    /// Stop after this many generations
+    #[arg(long)]
+    generations: Option<u64>,
+
+    /// Seed of the first random soup (R key or --headless) and of --birth-chance, --survival-chance, --noise
+    /// and --update.
+    /// Each R press after that moves on to the next seed
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Chance of each cell of a random soup being alive, from 0.0 to 1.0
+    #[arg(long, default_value_t = 0.5, value_parser = parse_density)]
+    density: f64,
+
+    /// Only fill a FILL_SIZE x FILL_SIZE box in the middle of the window with random soups
+    #[arg(long)]
+    fill_size: Option<u32>,
+
+    /// Symmetry forced onto random soups: C1 (none), C2, C4, D2x, D2y, D4 or D8. S cycles through them
+    #[arg(long, default_value = "C1")]
+    symmetry: Symmetry,
+
+    /// Chance of a birth the rule asks for happening, from 0.0 to 1.0 (game engine only)
+    #[arg(long, default_value_t = 1.0, value_parser = parse_chance)]
+    birth_chance: f64,
+
+    /// Chance of a survival the rule asks for happening, from 0.0 to 1.0 (game engine only)
+    #[arg(long, default_value_t = 1.0, value_parser = parse_chance)]
+    survival_chance: f64,
+
+    /// Chance of each cell being flipped every generation on top of the rule, from 0.0 to 1.0 (game engine only)
+    #[arg(long, default_value_t = 0.0, value_parser = parse_chance)]
+    noise: f64,
+
+    /// Order cells are updated in: synchronous (all at once), random-sequential (one random cell at a time,
+    /// as many times as there are cells), random-order (every cell once, in a random order) or alpha:<chance>
+    /// (all at once, each cell with that chance of being updated). Game engine only
+    #[arg(long, default_value = "synchronous")]
+    update: Update,
+
+    /// How many threads a bounded grid is stepped with (each one takes a stripe of the grid)
+    #[arg(long, default_value_t = 1)]
+    threads: usize,
 }
 
+/// Anything `--rule` accepts
+#[derive(Clone)]
+enum RuleArg {
+    Life(Rule),
+    LargerThanLife(LtlRule),
+    Elementary(ElementaryRule),
+}
+
+impl std::str::FromStr for RuleArg {
+    type Err = gol_engine::ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim_start().starts_with(['R', 'r']) {
+            s.parse().map(RuleArg::LargerThanLife)
+        } else if s.trim_start().starts_with(['W', 'w']) {
+            s.parse().map(RuleArg::Elementary)
+        } else {
+            s.parse().map(RuleArg::Life)
+        }
+    }
+}
+
+/// `--density` has to be a chance
+fn parse_density(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(d) if (0.0..=1.0).contains(&d) => Ok(d),
+        Ok(_) => Err("density has to be between 0.0 and 1.0".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// `--birth-chance`, `--survival-chance` and `--noise` are chances from 0.0 to 1.0
+fn parse_chance(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(c) if (0.0..=1.0).contains(&c) => Ok(c),
+        Ok(_) => Err("chances have to be between 0.0 and 1.0".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// `--kernel` is either the name of a built-in kernel or the path of a kernel file
+fn parse_kernel(s: &str) -> Result<Kernel, String> {
+    match s {
+        "moore" => Ok(Kernel::moore()),
+        "von-neumann" => Ok(Kernel::von_neumann()),
+        "hexagonal" => Ok(Kernel::hexagonal()),
+        path => std::fs::read_to_string(path)
+            .map_err(|e| format!("can't read {}: {}", path, e))?
+            .parse()
+            .map_err(|e: gol_engine::ParseKernelError| e.to_string()),
+    }
+}
+
+/// `--rule-table` is either wireworld or the path of a `.rule` file
+fn parse_rule_table(s: &str) -> Result<RuleTable, String> {
+    match s {
+        "wireworld" => Ok(RuleTable::wireworld()),
+        path => std::fs::read_to_string(path)
+            .map_err(|e| format!("can't read {}: {}", path, e))?
+            .parse()
+            .map_err(|e: gol_engine::ParseTableError| e.to_string()),
+    }
+}
+
+/// `--lenia` is either orbium or a Lenia rulestring
+fn parse_lenia(s: &str) -> Result<LeniaRule, String> {
+    match s {
+        "orbium" => Ok(LeniaRule::orbium()),
+        rule => rule
+            .parse()
+            .map_err(|e: gol_engine::ParseRuleError| e.to_string()),
+    }
+}
+
+/// Past this many live cells, the sidebar only looks for cycles when the engine lists the cells each
+/// step changed (hashing every cell would take longer than the step itself)
+const CYCLE_POPULATION_LIMIT: u64 = 1 << 20;
+
 /// Font
 const ROBOTO: &[u8; 167000] = include_bytes!("assets/Roboto-Light.ttf");
 
@@ -95,6 +306,7 @@ fn main() {
     let showing_h = height;
 
     let cube_size: u32 = cli.cube_size.unwrap_or(10);
+    let generations_per_step = 1u64 << cli.step_exponent;
 
     let vsync = cli.vsync;
     let record = cli.record;
@@ -102,6 +314,314 @@ fn main() {
     let output_still_frame = cli.output_still_frame;
     let next_simulation = cli.next_simulation;
 
+    // Shape cells are drawn as, and how many of them the window shows
+    let drawing = match &cli.rule {
+        RuleArg::Life(rule)
+            if cli.rule_table.is_none()
+                && cli.lenia.is_none()
+                && !(cli.skewed_hex && rule.tiling() == Tiling::Hexagonal) =>
+        {
+            rule.tiling()
+        }
+        _ => Tiling::Square,
+    };
+    let board = Board::new(drawing, width, height, cube_size);
+    let (columns, rows) = (board.columns, board.rows);
+
+    // Only plain Life rules without dying states come in colours
+    let colours = match &cli.rule {
+        _ if cli.colours == Colours::Single => Colours::Single,
+        RuleArg::Life(rule) if cli.rule_table.is_none() && cli.lenia.is_none() => {
+            if rule.states() == 2 {
+                cli.colours
+            } else {
+                println!(
+                    "{} has dying states, which don't come in colours, ignoring --colours",
+                    rule
+                );
+                Colours::Single
+            }
+        }
+        _ => {
+            println!("Only Life rules come in colours, ignoring --colours");
+            Colours::Single
+        }
+    };
+
+    // Seed of the first soup and of the stochastic rolls, which only plain Life rules have too
+    let seed = cli.seed.unwrap_or_else(|| Soup::random().seed);
+    let stochastic = Stochastic::new(seed)
+        .with_birth(cli.birth_chance)
+        .with_survival(cli.survival_chance)
+        .with_noise(cli.noise);
+    let stochastic = match &cli.rule {
+        _ if stochastic.is_deterministic() => None,
+        RuleArg::Life(_) if cli.rule_table.is_none() && cli.lenia.is_none() => Some(stochastic),
+        _ => {
+            println!("Only Life rules can be stochastic, ignoring --birth-chance, --survival-chance and --noise");
+            None
+        }
+    };
+    if let Some(stochastic) = stochastic {
+        println!("Stochastic: {}", stochastic);
+    }
+    let update = match &cli.rule {
+        RuleArg::Life(_) if cli.rule_table.is_none() && cli.lenia.is_none() => cli.update,
+        _ => {
+            if cli.update != Update::Synchronous {
+                println!("Only Life rules can be updated asynchronously, ignoring --update");
+            }
+            Update::Synchronous
+        }
+    };
+
+    // [`Engine`] picked with `--engine` ([`DenseGame`] unless asked otherwise)
+    let mut game: Box<dyn Engine> = match cli.rule.clone() {
+        _ if cli.lenia.is_some() => {
+            if cli.engine != EngineKind::Dense {
+                println!("Lenia has its own engine, ignoring --engine");
+            }
+            if cli.kernel.is_some() || cli.rule_table.is_some() {
+                println!("Lenia has its own kernel, ignoring --kernel and --rule-table");
+            }
+            let mut game = LeniaGame::new(columns, rows, cli.lenia.clone().unwrap());
+            game.set_topology(cli.topology);
+            Box::new(game)
+        }
+        _ if cli.rule_table.is_some() => {
+            if cli.engine != EngineKind::Dense {
+                println!("Rule tables have their own engine, ignoring --engine");
+            }
+            if cli.kernel.is_some() {
+                println!("Rule tables list their own neighbors, ignoring --kernel");
+            }
+            let mut game = TableGame::new(columns, rows, cli.rule_table.clone().unwrap());
+            game.set_topology(cli.topology);
+            Box::new(game)
+        }
+        RuleArg::Elementary(rule) => {
+            if cli.engine != EngineKind::Dense {
+                println!("Elementary rules have their own engine, ignoring --engine");
+            }
+            if cli.kernel.is_some() {
+                println!("Elementary rules only look left and right, ignoring --kernel");
+            }
+            if cli.topology == Topology::Sphere {
+                println!(
+                    "A single row can't be folded into a sphere, gluing its ends like a torus"
+                );
+            }
+            let mut game = ElementaryGame::new(columns, rows, rule);
+            game.set_topology(cli.topology);
+            if !cli.headless {
+                // start from a single cell in the middle of the top row
+                game.set_cell(columns as i32 / 2, 0, true);
+            }
+            Box::new(game)
+        }
+        RuleArg::LargerThanLife(rule) => {
+            if cli.engine != EngineKind::Dense {
+                println!("Larger than Life rules have their own engine, ignoring --engine");
+            }
+            if cli.kernel.is_some() {
+                println!("Larger than Life rules have their own neighborhood, ignoring --kernel");
+            }
+            let mut game = LtlGame::new(columns, rows, rule);
+            game.set_topology(cli.topology);
+            Box::new(game)
+        }
+        RuleArg::Life(rule) => {
+            let kernel = match &cli.kernel {
+                Some(_) if rule.tiling() != Tiling::Square => {
+                    println!(
+                        "--kernel replaces the neighbors of square cells, {} has {} ones, ignoring --kernel",
+                        rule,
+                        rule.tiling()
+                    );
+                    None
+                }
+                kernel => kernel.clone(),
+            };
+            let engine = if rule.states() > 2 && cli.engine != EngineKind::Game {
+                println!(
+                    "{} has dying states, only the game engine supports them. Using it",
+                    rule
+                );
+                EngineKind::Game
+            } else if rule.tiling() != Tiling::Square && cli.engine != EngineKind::Game {
+                println!(
+                    "{} has {} cells, only the game engine supports them. Using it",
+                    rule,
+                    rule.tiling()
+                );
+                EngineKind::Game
+            } else if kernel.is_some() && cli.engine != EngineKind::Game {
+                println!("Only the game engine supports --kernel. Using it");
+                EngineKind::Game
+            } else if colours != Colours::Single && cli.engine != EngineKind::Game {
+                println!("Only the game engine supports --colours. Using it");
+                EngineKind::Game
+            } else if stochastic.is_some() && cli.engine != EngineKind::Game {
+                println!("Only the game engine supports stochastic rules. Using it");
+                EngineKind::Game
+            } else if update != Update::Synchronous && cli.engine != EngineKind::Game {
+                println!("Only the game engine supports --update. Using it");
+                EngineKind::Game
+            } else if !rule.is_totalistic()
+                && matches!(cli.engine, EngineKind::Dense | EngineKind::Sparse)
+            {
+                println!(
+                    "{} isn't totalistic, only the game and hashlife engines support it. Using game",
+                    rule
+                );
+                EngineKind::Game
+            } else if rule.born(0)
+                && matches!(cli.engine, EngineKind::Sparse | EngineKind::HashLife)
+            {
+                // hashlife runs non-totalistic rules, dense doesn't
+                let (engine, name) = if rule.is_totalistic() {
+                    (EngineKind::Dense, "dense")
+                } else {
+                    (EngineKind::Game, "game")
+                };
+                println!(
+                    "{} has B0, which would fill an endless universe in one generation. Using {}",
+                    rule, name
+                );
+                engine
+            } else {
+                cli.engine
+            };
+            match engine {
+                EngineKind::Game => {
+                    let mut game = Game::new(columns, rows, cube_size);
+                    game.set_rule(rule);
+                    game.set_kernel(kernel);
+                    game.set_colours(colours);
+                    game.set_stochastic(stochastic);
+                    game.set_update(update, seed);
+                    game.set_topology(cli.topology);
+                    game.set_threads(cli.threads);
+                    Box::new(game)
+                }
+                EngineKind::Dense => {
+                    let mut game = DenseGame::new(columns, rows);
+                    game.set_rule(rule);
+                    game.set_topology(cli.topology);
+                    game.set_threads(cli.threads);
+                    Box::new(game)
+                }
+                EngineKind::Sparse => {
+                    let mut game = SparseGame::new();
+                    game.set_rule(rule);
+                    Box::new(game)
+                }
+                EngineKind::HashLife => {
+                    let mut game = HashLifeGame::new();
+                    game.set_rule(rule);
+                    Box::new(game)
+                }
+            }
+        }
+    };
+    let unbounded = game.topology().is_none();
+    if game.topology() == Some(Topology::Sphere) && columns != rows {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                format!(
+                    "--topology sphere needs a square grid, but --width / --cube-size gives {} columns and \
+                     --height / --cube-size gives {} rows",
+                    columns, rows
+                ),
+            )
+            .exit();
+    }
+    // Colours the @COLORS section of the rule table gives each state, or the ones of the teams
+    let palette = match &cli.rule_table {
+        Some(table) => (0..table.states())
+            .map(|state| {
+                table
+                    .color(state)
+                    .map(|(r, g, b)| sdl2::pixels::Color::RGB(r, g, b))
+            })
+            .collect(),
+        None if colours != Colours::Single => std::iter::once(None)
+            .chain(
+                TEAM_COLORS
+                    .into_iter()
+                    .take(colours.count() as usize)
+                    .map(Some),
+            )
+            .collect(),
+        None => Vec::new(),
+    };
+    // State the mouse draws with (the number keys pick another one)
+    let mut paint_state = LifeState::Alive;
+    // Lenia cells are drawn full or empty, whatever value they round to
+    let paint_states = if cli.lenia.is_some() {
+        2
+    } else {
+        game.states()
+    };
+    // Past generations for Backspace to go back to
+    let mut history = History::new(cli.history_memory << 20);
+    // Hashes of the last generations, to notice when the grid repeats itself
+    let mut detector = CycleDetector::new(4096);
+    let mut shown_cycle = None;
+    // Next random soup R fills the window with, and the last one it did
+    let mut next_soup = Soup::new(seed)
+        .with_density(cli.density)
+        .with_size(cli.fill_size)
+        .with_symmetry(cli.symmetry)
+        .with_colours(colours.count());
+    let mut soup: Option<Soup> = None;
+    // elementary rules only read their top row, so that's all a soup fills
+    let soup_rows = match cli.rule {
+        RuleArg::Elementary(_) => 1,
+        _ => rows,
+    };
+    // `--stats-out` file, one row per simulation step
+    let mut stats = cli.stats_out.as_ref().map(|path| {
+        let file = std::fs::File::create(path).expect("Failed to create the stats file");
+        StatsWriter::new(std::io::BufWriter::new(file), StatsFormat::from_path(path))
+            .expect("Failed to write to the stats file")
+    });
+    // whether the generation the window started simulating from has its row yet
+    let mut stats_started = false;
+
+    if cli.headless {
+        // nobody can draw anything, so start from a random grid the size of the window
+        next_soup.fill(&mut *game, 0, 0, columns, soup_rows);
+        println!("Soup: {}", next_soup);
+        loop {
+            if let Some(s) = stats.as_mut() {
+                s.write(&GenerationStats::of(&*game))
+                    .expect("Failed to write to the stats file");
+            }
+            if cli.stop_when_stable && detector.observe(&*game).is_some() {
+                println!("{}", stability_text(detector.cycle()));
+                break;
+            }
+            if cli
+                .generations
+                .is_some_and(|limit| game.generation() >= limit as u128)
+            {
+                break;
+            }
+            game.step(generations_per_step);
+        }
+        println!(
+            "Generation {}: {} alive",
+            format_big_number(game.generation()),
+            format_big_number(game.population() as u128)
+        );
+        if let Some(mut s) = stats {
+            s.flush().expect("Failed to write to the stats file");
+        }
+        return;
+    }
+
     // Initialize SDL2
     let ctx = sdl2::init().unwrap();
     let video = ctx.video().unwrap();
@@ -123,24 +643,8 @@ fn main() {
 
     let mut event = ctx.event_pump().unwrap();
 
-    let mut cubes: HashMap<(i32, i32), Life> = HashMap::new();
-
-    // Initialize the cubes
-    for y in 0..(height / cube_size) as i32 {
-        for x in 0..(width / cube_size) as i32 {
-            cubes.insert(
-                (x * cube_size as i32, y * cube_size as i32),
-                Life {
-                    x: x * cube_size as i32,
-                    y: y * cube_size as i32,
-                    state: LifeState::Dead,
-                },
-            );
-        }
-    }
-
-    // [`Game`] instance
-    let mut game = Game { cubes, cube_size };
+    // Top left cell of the window (only moves around when it's unbounded)
+    let mut view: (i64, i64) = (0, 0);
     let tc = canvas.texture_creator();
 
     let mut update_time = std::time::Instant::now();
@@ -187,6 +691,48 @@ fn main() {
         sdl2::pixels::Color::WHITE,
         sdl2::pixels::Color::BLACK,
     );
+    let rendered_rewind_sim_text = render_text_as_texture(
+        word_wrap(
+            if history.capacity() > 0 && game.can_rewind() {
+                "Press Backspace while paused to go back one generation"
+            } else {
+                ""
+            },
+            showing_w - width,
+            &fps_font,
+        )
+        .into_iter(),
+        &fps_font,
+        &tc,
+        sdl2::pixels::Color::WHITE,
+        sdl2::pixels::Color::BLACK,
+    );
+    let mut rendered_seed_text = render_text_as_texture(
+        word_wrap(
+            &soup_text(soup, next_soup.symmetry),
+            showing_w - width,
+            &fps_font,
+        )
+        .into_iter(),
+        &fps_font,
+        &tc,
+        sdl2::pixels::Color::WHITE,
+        sdl2::pixels::Color::BLACK,
+    );
+    let mut rendered_stable_text = render_text_as_texture(
+        word_wrap(&stability_text(None), showing_w - width, &fps_font).into_iter(),
+        &fps_font,
+        &tc,
+        sdl2::pixels::Color::WHITE,
+        sdl2::pixels::Color::BLACK,
+    );
+    let rendered_noise_text = render_text_as_texture(
+        word_wrap(&noise_text(stochastic), showing_w - width, &fps_font).into_iter(),
+        &fps_font,
+        &tc,
+        sdl2::pixels::Color::WHITE,
+        sdl2::pixels::Color::BLACK,
+    );
     let rendered_draw_sim_text = render_text_as_texture(
         word_wrap(
             "You can hold your left mouse button to draw a shape",
@@ -199,10 +745,38 @@ fn main() {
         sdl2::pixels::Color::WHITE,
         sdl2::pixels::Color::BLACK,
     );
+    let mut rendered_paint_text = render_text_as_texture(
+        word_wrap(
+            &paint_text(paint_state, paint_states),
+            showing_w - width,
+            &fps_font,
+        )
+        .into_iter(),
+        &fps_font,
+        &tc,
+        sdl2::pixels::Color::WHITE,
+        sdl2::pixels::Color::BLACK,
+    );
+    let rendered_pan_sim_text = render_text_as_texture(
+        word_wrap(
+            if unbounded {
+                "Use the arrow keys to move around the universe"
+            } else {
+                ""
+            },
+            showing_w - width,
+            &fps_font,
+        )
+        .into_iter(),
+        &fps_font,
+        &tc,
+        sdl2::pixels::Color::WHITE,
+        sdl2::pixels::Color::BLACK,
+    );
     let rendered_status_text = render_text_as_texture(
         word_wrap(
             &format!(
-                "Recording: {}\nLength: {}\nNext Simulation: {}ms",
+                "Recording: {}\nLength: {}\nNext Simulation: {}ms\nRule: {}\nTopology: {}",
                 if record { "ON" } else { "OFF" },
                 if let Some(l) = length {
                     format!(
@@ -214,7 +788,10 @@ fn main() {
                 } else {
                     "N/A".to_string()
                 },
-                next_simulation
+                next_simulation,
+                game.rule_name(),
+                game.topology()
+                    .map_or("unbounded".to_string(), |t| t.to_string())
             ),
             showing_w - width,
             &fps_font,
@@ -238,20 +815,8 @@ fn main() {
     let mut vr: Option<std::sync::Arc<std::sync::Mutex<ffmpeg::VideoRecorder>>> = None;
 
     if record {
-        vr = Some(std::sync::Arc::new(std::sync::Mutex::new(
-            ffmpeg::VideoRecorder::new(
-                "out.mp4",
-                width,
-                height,
-                video.desktop_display_mode(0).unwrap().refresh_rate as u32,
-            ),
-        )));
-        let cloned_vr = std::sync::Arc::clone(&vr.clone().unwrap());
+        // FFMpeg only starts with the simulation, so the soup it was started from can be saved with the video
         println!("Recording will start once started simulation...");
-        ctrlc::set_handler(move || {
-            cloned_vr.lock().unwrap().kill();
-        })
-        .expect("Failed to listen for CTRL-C (Force exiting with FFMpeg)");
     } else {
         println!("Playing normally...");
     }
@@ -260,18 +825,20 @@ fn main() {
         .create_texture(None, sdl2::render::TextureAccess::Target, width, height)
         .unwrap();
     grid_texture.set_blend_mode(sdl2::render::BlendMode::Blend);
-    create_grid_texture(&mut canvas, &mut grid_texture, width, height, cube_size);
+    create_grid_texture(&mut canvas, &mut grid_texture, &board);
 
+    // squares are drawn a texel per cell and stretched over the board, other shapes a texel per pixel
+    let (texture_width, texture_height) = if board.pixel_cells.is_empty() {
+        (columns, rows)
+    } else {
+        (width, height)
+    };
     let mut cell_texture = tc
-        .create_texture_streaming(
-            None,
-            (width / cube_size) as u32,
-            (height / cube_size) as u32,
-        )
+        .create_texture_streaming(None, texture_width, texture_height)
         .unwrap();
 
     let mut run_sim = false;
-    let mut last_cord = (0, 0);
+    let mut last_cord = None;
 
     'main_loop: loop {
         for e in event.poll_iter() {
@@ -281,7 +848,7 @@ fn main() {
                     ..
                 } => {
                     // no idea why you have to redraw the grid every resizes...
-                    create_grid_texture(&mut canvas, &mut grid_texture, width, height, cube_size);
+                    create_grid_texture(&mut canvas, &mut grid_texture, &board);
                 }
                 sdl2::event::Event::Quit { .. }
                 | sdl2::event::Event::KeyDown {
@@ -296,6 +863,30 @@ fn main() {
                         run_sim = !run_sim;
                     } else {
                         run_sim = true;
+                        if vr.is_none() {
+                            let recorder = std::sync::Arc::new(std::sync::Mutex::new(
+                                ffmpeg::VideoRecorder::new(
+                                    "out.mp4",
+                                    width,
+                                    height,
+                                    video.desktop_display_mode(0).unwrap().refresh_rate as u32,
+                                    &format!(
+                                        "rule={} topology={} engine={}{}",
+                                        game.rule_name(),
+                                        game.topology()
+                                            .map_or("unbounded".to_string(), |t| t.to_string()),
+                                        game.name(),
+                                        soup.map_or(String::new(), |s| format!(" {}", s))
+                                    ),
+                                ),
+                            ));
+                            let cloned_vr = std::sync::Arc::clone(&recorder);
+                            ctrlc::set_handler(move || {
+                                cloned_vr.lock().unwrap().kill();
+                            })
+                            .expect("Failed to listen for CTRL-C (Force exiting with FFMpeg)");
+                            vr = Some(recorder);
+                        }
                     }
                     rendered_play_sim_text = render_text_as_texture(word_wrap(
                             if run_sim {"Running simulation. Press Space to pause it. (You can't pause while recording, however.)"} else {"Press Space to start the simulation (Will also start recording if it's on)"},
@@ -314,48 +905,111 @@ fn main() {
                     ..
                 } => {
                     if !run_sim {
-                        game.cubes = {
-                            let mut new_cubes = HashMap::new();
-                            for (pos, life) in game.cubes.iter() {
-                                new_cubes.insert(
-                                    *pos,
-                                    Life {
-                                        x: life.x,
-                                        y: life.y,
-                                        state: LifeState::random_life_state(),
-                                    },
-                                );
-                            }
-                            new_cubes
-                        }
+                        next_soup.fill(&mut *game, view.0, view.1, columns, soup_rows);
+                        soup = Some(next_soup);
+                        next_soup = next_soup.next();
+                        rendered_seed_text = render_text_as_texture(
+                            word_wrap(
+                                &soup_text(soup, next_soup.symmetry),
+                                showing_w - width,
+                                &fps_font,
+                            )
+                            .into_iter(),
+                            &fps_font,
+                            &tc,
+                            sdl2::pixels::Color::WHITE,
+                            sdl2::pixels::Color::BLACK,
+                        );
+                        history.clear();
+                        detector.clear();
                     }
                 }
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(sdl2::keyboard::Keycode::S),
+                    ..
+                } => {
+                    next_soup = next_soup.with_symmetry(next_soup.symmetry.next());
+                    rendered_seed_text = render_text_as_texture(
+                        word_wrap(
+                            &soup_text(soup, next_soup.symmetry),
+                            showing_w - width,
+                            &fps_font,
+                        )
+                        .into_iter(),
+                        &fps_font,
+                        &tc,
+                        sdl2::pixels::Color::WHITE,
+                        sdl2::pixels::Color::BLACK,
+                    );
+                }
                 sdl2::event::Event::KeyDown {
                     keycode: Some(sdl2::keyboard::Keycode::C),
                     ..
                 } => {
                     if !run_sim {
-                        game.cubes.iter_mut().for_each(|(_, l)| {
-                            l.state = LifeState::Dead;
-                        });
+                        game.clear();
+                        history.clear();
+                        detector.clear();
+                    }
+                }
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(sdl2::keyboard::Keycode::Backspace),
+                    ..
+                } if !run_sim => {
+                    history.rewind(&mut *game);
+                    detector.clear();
+                }
+                sdl2::event::Event::KeyDown {
+                    keycode: Some(k), ..
+                } if k
+                    .name()
+                    .parse::<u8>()
+                    .is_ok_and(|state| state < paint_states) =>
+                {
+                    paint_state = LifeState::from_index(k.name().parse().unwrap());
+                    rendered_paint_text = render_text_as_texture(
+                        word_wrap(
+                            &paint_text(paint_state, paint_states),
+                            showing_w - width,
+                            &fps_font,
+                        )
+                        .into_iter(),
+                        &fps_font,
+                        &tc,
+                        sdl2::pixels::Color::WHITE,
+                        sdl2::pixels::Color::BLACK,
+                    );
+                }
+                sdl2::event::Event::KeyDown {
+                    keycode:
+                        Some(
+                            k @ (sdl2::keyboard::Keycode::Left
+                            | sdl2::keyboard::Keycode::Right
+                            | sdl2::keyboard::Keycode::Up
+                            | sdl2::keyboard::Keycode::Down),
+                        ),
+                    ..
+                } if unbounded => {
+                    // move a tenth of the window at a time
+                    let (dx, dy) = ((columns / 10).max(1) as i64, (rows / 10).max(1) as i64);
+                    match k {
+                        sdl2::keyboard::Keycode::Left => view.0 -= dx,
+                        sdl2::keyboard::Keycode::Right => view.0 += dx,
+                        sdl2::keyboard::Keycode::Up => view.1 -= dy,
+                        _ => view.1 += dy,
                     }
                 }
                 sdl2::event::Event::MouseButtonDown {
                     x, y, mouse_btn, ..
                 } => {
                     // round them again
-                    if !run_sim {
-                        let x = x / cube_size as i32 * cube_size as i32;
-                        let y = y / cube_size as i32 * cube_size as i32;
-                        if let Some(life) = game.cubes.get_mut(&(x, y)) {
-                            if mouse_btn == sdl2::mouse::MouseButton::Left {
-                                life.state = if life.state == LifeState::Alive {
-                                    LifeState::Dead
-                                } else {
-                                    LifeState::Alive
-                                }
-                            }
-                        }
+                    if let Some((x, y)) = board
+                        .cell_under(x, y)
+                        .filter(|_| !run_sim && mouse_btn == sdl2::mouse::MouseButton::Left)
+                    {
+                        game.paint_cell(view.0 + x, view.1 + y, paint_state);
+                        history.clear();
+                        detector.clear();
                     }
                 }
                 sdl2::event::Event::MouseMotion {
@@ -364,21 +1018,16 @@ fn main() {
                     // println!("Mouse at ({}, {})", x, y);
                     // round those cord to nearest cube
                     if !run_sim {
-                        let x = x / cube_size as i32 * cube_size as i32;
-                        let y = y / cube_size as i32 * cube_size as i32;
-                        if (x, y) == last_cord {
+                        let cell = board.cell_under(x, y);
+                        if cell == last_cord {
                             continue;
                         }
-                        if let Some(life) = game.cubes.get_mut(&(x, y)) {
-                            if mousestate.left() {
-                                life.state = if life.state == LifeState::Alive {
-                                    LifeState::Dead
-                                } else {
-                                    LifeState::Alive
-                                }
-                            }
+                        if let Some((x, y)) = cell.filter(|_| mousestate.left()) {
+                            game.paint_cell(view.0 + x, view.1 + y, paint_state);
+                            history.clear();
+                            detector.clear();
                         }
-                        last_cord = (x, y);
+                        last_cord = cell;
                     }
                 }
                 _ => {}
@@ -397,22 +1046,36 @@ fn main() {
 
         cell_texture
             .with_lock(None, |buffer: &mut [u8], pitch: usize| {
-                for y in 0..(height / cube_size) as usize {
-                    for x in 0..(width / cube_size) as usize {
-                        let idx = y * pitch + x * 4;
-                        let alive = game.cubes
-                            [&(x as i32 * cube_size as i32, y as i32 * cube_size as i32)]
-                            .state
-                            == LifeState::Alive;
-                        let color = if alive {
-                            sdl2::pixels::Color::WHITE
-                        } else {
-                            sdl2::pixels::Color::GRAY
-                        };
-                        buffer[idx + 0] = color.r;
-                        buffer[idx + 1] = color.g;
-                        buffer[idx + 2] = color.b;
-                        buffer[idx + 3] = color.a;
+                if board.pixel_cells.is_empty() {
+                    for y in 0..rows as usize {
+                        for x in 0..columns as usize {
+                            let idx = y * pitch + x * 4;
+                            let (x, y) = (view.0 + x as i64, view.1 + y as i64);
+                            let color = game.value(x, y).map_or_else(
+                                || life_state_color(game.get_cell(x, y), game.states(), &palette),
+                                value_color,
+                            );
+                            buffer[idx + 0] = color.r;
+                            buffer[idx + 1] = color.g;
+                            buffer[idx + 2] = color.b;
+                            buffer[idx + 3] = color.a;
+                        }
+                    }
+                } else {
+                    // colour every cell once, then every pixel like the cell under it
+                    let colors = (0..rows as i64)
+                        .flat_map(|y| (0..columns as i64).map(move |x| (x, y)))
+                        .map(|(x, y)| {
+                            game.value(x, y).map_or_else(
+                                || life_state_color(game.get_cell(x, y), game.states(), &palette),
+                                value_color,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    for (i, cell) in board.pixel_cells.iter().enumerate() {
+                        let idx = (i / width as usize) * pitch + (i % width as usize) * 4;
+                        let color = cell.map_or(sdl2::pixels::Color::BLACK, |c| colors[c]);
+                        buffer[idx..idx + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
                     }
                 }
             })
@@ -455,17 +1118,18 @@ fn main() {
             lft = std::time::Instant::now();
         }
         let elasped = update_time.elapsed();
+        let mut stepped = false;
         if (elasped.as_millis() >= next_simulation as u128 && !record) && run_sim {
             update_time = std::time::Instant::now();
-            game.apply_rules_to_each_lifes();
+            stepped = true;
         } else if run_sim {
             if output_still_frame && record {
                 if elasped.as_millis() >= next_simulation as u128 {
                     update_time = std::time::Instant::now();
-                    game.apply_rules_to_each_lifes();
+                    stepped = true;
                 }
             } else if record {
-                game.apply_rules_to_each_lifes();
+                stepped = true;
             }
             if let Some(v) = vr.as_mut() {
                 let mut v = v.lock().unwrap();
@@ -486,6 +1150,58 @@ fn main() {
                 }
             }
         }
+        if stepped {
+            let watch = cli.stop_when_stable || game.population() <= CYCLE_POPULATION_LIMIT;
+            // the generation before the first step counts too, like in headless mode
+            if watch && detector.is_empty() {
+                detector.observe(&*game);
+            }
+            if !stats_started {
+                if let Some(s) = stats.as_mut() {
+                    s.write(&GenerationStats::of(&*game))
+                        .expect("Failed to write to the stats file");
+                }
+                stats_started = true;
+            }
+            let generation = game.generation();
+            // the cells a step changed feed both the history and the cycle hash
+            match game.step_with_changes(generations_per_step) {
+                Some(changes) => {
+                    detector.observe_changes(&*game, &changes);
+                    history.record(generation, changes);
+                }
+                None => {
+                    history.clear();
+                    if watch {
+                        detector.observe(&*game);
+                    }
+                }
+            }
+            if let Some(s) = stats.as_mut() {
+                s.write(&GenerationStats::of(&*game))
+                    .expect("Failed to write to the stats file");
+            }
+            if cli
+                .generations
+                .is_some_and(|limit| game.generation() >= limit as u128)
+            {
+                break 'main_loop;
+            }
+        }
+        if detector.cycle() != shown_cycle {
+            shown_cycle = detector.cycle();
+            rendered_stable_text = render_text_as_texture(
+                word_wrap(&stability_text(shown_cycle), showing_w - width, &fps_font).into_iter(),
+                &fps_font,
+                &tc,
+                sdl2::pixels::Color::WHITE,
+                sdl2::pixels::Color::BLACK,
+            );
+            if shown_cycle.is_some() && cli.stop_when_stable {
+                println!("{}", stability_text(shown_cycle));
+                break 'main_loop;
+            }
+        }
         let fps_text = fps_font
             .render(&format!("FPS: {}", truncate(fps, 2)))
             .shaded(sdl2::pixels::Color::WHITE, sdl2::pixels::Color::BLACK)
@@ -498,6 +1214,13 @@ fn main() {
             .render(&format!("Minimum FPS: {}", truncate(lf, 2)))
             .shaded(sdl2::pixels::Color::WHITE, sdl2::pixels::Color::BLACK)
             .unwrap();
+        let generation_text = fps_font
+            .render(&format!(
+                "Generation: {}",
+                format_big_number(game.generation())
+            ))
+            .shaded(sdl2::pixels::Color::WHITE, sdl2::pixels::Color::BLACK)
+            .unwrap();
         canvas
             .copy(
                 &tc.create_texture_from_surface(&fps_text).unwrap(),
@@ -534,12 +1257,30 @@ fn main() {
                 ),
             )
             .unwrap();
-        let mut ys = 120u32;
+        canvas
+            .copy(
+                &tc.create_texture_from_surface(&generation_text).unwrap(),
+                None,
+                sdl2::rect::Rect::new(
+                    (showing_w - generation_text.width()) as i32,
+                    120,
+                    generation_text.width(),
+                    generation_text.height(),
+                ),
+            )
+            .unwrap();
+        let mut ys = 160u32;
         let groups = [
             &rendered_clear_sim_text,
             &rendered_rand_sim_text,
             &rendered_status_text,
+            &rendered_seed_text,
+            &rendered_stable_text,
+            &rendered_noise_text,
             &rendered_draw_sim_text,
+            &rendered_paint_text,
+            &rendered_rewind_sim_text,
+            &rendered_pan_sim_text,
             &rendered_play_sim_text,
         ];
         groups.iter().for_each(|g| {
@@ -563,6 +1304,9 @@ fn main() {
 
         canvas.present();
     }
+    if let Some(mut s) = stats {
+        s.flush().expect("Failed to write to the stats file");
+    }
     // Done feeding frames. Now showing result
     if let Some(v) = vr {
         let mut a = v.lock().unwrap();
@@ -3,6 +3,67 @@ pub fn truncate(b: f64, precision: usize) -> f64 {
     f64::trunc(b * ((10 * precision) as f64)) / ((10 * precision) as f64)
 }
 
+/// Format huge numbers with thousands separators (`1099511627776` becomes `1,099,511,627,776`)
+pub fn format_big_number(n: u128) -> String {
+    let digits = n.to_string();
+    let (head, tail) = digits.split_at(digits.len() % 3);
+    std::iter::once(head)
+        .filter(|h| !h.is_empty())
+        .chain(
+            tail.as_bytes()
+                .chunks(3)
+                .map(|c| std::str::from_utf8(c).unwrap()),
+        )
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Sidebar text for what [`gol_engine::CycleDetector`] found so far
+pub fn stability_text(cycle: Option<gol_engine::Cycle>) -> String {
+    match cycle {
+        Some(cycle) => format!(
+            "Stabilised at gen {}, period {}",
+            format_big_number(cycle.start),
+            format_big_number(cycle.period)
+        ),
+        None => "Not stable yet".to_string(),
+    }
+}
+
+/// Sidebar text for the last random soup and the symmetry R uses next
+pub fn soup_text(soup: Option<gol_engine::Soup>, symmetry: gol_engine::Symmetry) -> String {
+    format!(
+        "Seed: {}\nSymmetry: {} (press S to change)",
+        soup.map_or("none (press R)".to_string(), |s| s.seed.to_string()),
+        symmetry
+    )
+}
+
+/// Sidebar text for the chances of a stochastic rule (empty when the rule is followed to the letter)
+pub fn noise_text(stochastic: Option<gol_engine::Stochastic>) -> String {
+    stochastic.map_or_else(String::new, |s| {
+        format!(
+            "Noise: {:.2}% of cells flipped per generation\nBirths: {:.0}%, survivals: {:.0}%",
+            s.noise * 100.0,
+            s.birth * 100.0,
+            s.survival * 100.0
+        )
+    })
+}
+
+/// Sidebar text for the state the mouse draws with (empty when cells are only ever alive or dead)
+pub fn paint_text(state: gol_engine::LifeState, states: u8) -> String {
+    if states > 2 {
+        format!(
+            "Drawing with state {} (press 0-{} to change)",
+            state.index(),
+            (states - 1).min(9)
+        )
+    } else {
+        String::new()
+    }
+}
+
 /// Basic word wrap based on [`sdl2::ttf::Font`] and [`max_width`]
 pub fn word_wrap(text: &str, max_width: u32, font: &sdl2::ttf::Font<'_, '_>) -> Vec<String> {
     let mut lines: Vec<String> = Vec::new();
@@ -32,6 +93,60 @@ pub fn word_wrap(text: &str, max_width: u32, font: &sdl2::ttf::Font<'_, '_>) ->
     lines
 }
 
+/// Colours of the teams of [`gol_engine::Colours`], team 1 first
+pub const TEAM_COLORS: [sdl2::pixels::Color; 4] = [
+    sdl2::pixels::Color::RGB(220, 50, 50),
+    sdl2::pixels::Color::RGB(60, 110, 230),
+    sdl2::pixels::Color::RGB(60, 180, 75),
+    sdl2::pixels::Color::RGB(240, 200, 40),
+];
+
+/// Colour of a cell in [`gol_engine::LifeState`] for a rule with `states` states, taken from `palette`
+/// (indexed by [`gol_engine::LifeState::index`]) when it has one.
+/// Dying cells fade from orange to the dead colour as they get closer to dying
+pub fn life_state_color(
+    state: gol_engine::LifeState,
+    states: u8,
+    palette: &[Option<sdl2::pixels::Color>],
+) -> sdl2::pixels::Color {
+    if let Some(color) = palette.get(state.index() as usize).copied().flatten() {
+        return color;
+    }
+    let dead = sdl2::pixels::Color::GRAY;
+    match state {
+        gol_engine::LifeState::Alive => sdl2::pixels::Color::WHITE,
+        gol_engine::LifeState::Dead => dead,
+        gol_engine::LifeState::Dying(k) => {
+            let dying = sdl2::pixels::Color::RGB(255, 140, 0);
+            // 0.0 on the first dying state, 1.0 on the last one
+            let t = (k - 1) as f32 / (states.saturating_sub(2).max(2) - 1) as f32;
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t * 0.8) as u8;
+            sdl2::pixels::Color::RGB(
+                mix(dying.r, dead.r),
+                mix(dying.g, dead.g),
+                mix(dying.b, dead.b),
+            )
+        }
+    }
+}
+
+/// Colour of a continuous cell holding `value` (0 to 1), on a viridis-like scale from dark purple to yellow
+pub fn value_color(value: f32) -> sdl2::pixels::Color {
+    const STOPS: [(u8, u8, u8); 5] = [
+        (68, 1, 84),
+        (59, 82, 139),
+        (33, 145, 140),
+        (94, 201, 98),
+        (253, 231, 37),
+    ];
+    let at = value.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let i = (at as usize).min(STOPS.len() - 2);
+    let t = at - i as f32;
+    let (a, b) = (STOPS[i], STOPS[i + 1]);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    sdl2::pixels::Color::RGB(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
 pub fn render_text_as_texture<'a, T>(
     segments: impl Iterator<Item = (impl AsRef<str> + 'a)>,
     font: &sdl2::ttf::Font<'a, 'a>,
@@ -53,18 +168,101 @@ pub fn render_text_as_texture<'a, T>(
         .collect()
 }
 
+/// The part of the window cells are drawn on, and which cell each of its pixels shows
+pub struct Board {
+    pub width: u32,
+    pub height: u32,
+    pub cube_size: u32,
+    /// Size of the grid the board shows (wider than the window for hexagons and triangles)
+    pub columns: u32,
+    pub rows: u32,
+    /// Index (`y * columns + x`) of the cell under every pixel, row by row. Empty for squares, which are
+    /// simply drawn one texel per cell and stretched
+    pub pixel_cells: Vec<Option<usize>>,
+}
+
+impl Board {
+    /// Board of a `width` x `height` window drawing cells as `drawing`
+    pub fn new(drawing: gol_engine::Tiling, width: u32, height: u32, cube_size: u32) -> Self {
+        let (w, h) = (width / cube_size, height / cube_size);
+        let (columns, rows) = match drawing {
+            gol_engine::Tiling::Square => (w, h),
+            // every row is drawn half a cell left of the one above, so the bottom rows need more cells
+            gol_engine::Tiling::Hexagonal => (w + h.div_ceil(2), h),
+            // triangles are half a cell apart
+            gol_engine::Tiling::Triangular => (2 * w - 1, h),
+        };
+        let mut pixel_cells = Vec::new();
+        if drawing != gol_engine::Tiling::Square {
+            pixel_cells.reserve((width * height) as usize);
+            for py in 0..height {
+                for px in 0..width {
+                    let (x, y) = drawing.cell_at(
+                        (px as f64 + 0.5) / cube_size as f64,
+                        (py as f64 + 0.5) / cube_size as f64,
+                    );
+                    pixel_cells.push(
+                        ((0..columns as i64).contains(&x) && (0..rows as i64).contains(&y))
+                            .then_some(y as usize * columns as usize + x as usize),
+                    );
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            cube_size,
+            columns,
+            rows,
+            pixel_cells,
+        }
+    }
+
+    /// Cell under the pixel (`x`, `y`) of the window (`None` off the board)
+    pub fn cell_under(&self, x: i32, y: i32) -> Option<(i64, i64)> {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return None;
+        }
+        if self.pixel_cells.is_empty() {
+            return Some((
+                (x as u32 / self.cube_size) as i64,
+                (y as u32 / self.cube_size) as i64,
+            ));
+        }
+        self.pixel_cells[(y as u32 * self.width + x as u32) as usize].map(|i| {
+            (
+                (i % self.columns as usize) as i64,
+                (i / self.columns as usize) as i64,
+            )
+        })
+    }
+}
+
 pub fn create_grid_texture<T: sdl2::render::RenderTarget>(
     canvas: &mut sdl2::render::Canvas<T>,
     grid_texture: &mut sdl2::render::Texture<'_>,
-    width: u32,
-    height: u32,
-    cube_size: u32,
+    board: &Board,
 ) {
+    let (width, height, cube_size) = (board.width, board.height, board.cube_size);
     canvas
         .with_texture_canvas(grid_texture, |texture| {
             texture.set_draw_color(sdl2::pixels::Color::RGBA(0, 0, 0, 0));
             texture.clear();
             texture.set_draw_color(sdl2::pixels::Color::BLACK);
+            if !board.pixel_cells.is_empty() {
+                // outline hexagons and triangles where the cell changes from one pixel to the next
+                let cell = |x: u32, y: u32| board.pixel_cells[(y * width + x) as usize];
+                let edges = (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (x, y)))
+                    .filter(|&(x, y)| {
+                        (x + 1 < width && cell(x, y) != cell(x + 1, y))
+                            || (y + 1 < height && cell(x, y) != cell(x, y + 1))
+                    })
+                    .map(|(x, y)| sdl2::rect::Point::new(x as i32, y as i32))
+                    .collect::<Vec<_>>();
+                texture.draw_points(edges.as_slice()).unwrap();
+                return;
+            }
             for y in (0..height).step_by(cube_size as usize) {
                 texture
                     .draw_line(